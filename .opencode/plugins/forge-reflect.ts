@@ -4,27 +4,26 @@ import path from "path"
 /**
  * forge-reflect opencode plugin adapter.
  *
- * Bridges the Rust binaries (surface, insight, reflect) into opencode's
- * event-based plugin system. Covers three hook events:
+ * Bridges the Rust `forge-reflect` binary into opencode's event-based plugin
+ * system. Every command runs with `--host opencode`, which prints either
+ * `{"action":"toast","level","message"}` or `{"action":"context","text"}`;
+ * config problems ride along in the toast message or a context `notice`.
  *
  * - session.created  -> surface digest (SessionStart equivalent)
- * - session.compacting -> reflect in PreCompact mode
- * - session.idle     -> insight + reflect in Stop mode (TUI toast, non-blocking)
+ * - session.compacting -> precompact reflection prompt
+ * - session.idle     -> stop gates (TUI toast, non-blocking)
  */
 export const ForgeReflect: Plugin = async ({ $, directory, worktree }) => {
   const moduleRoot = worktree || directory
-  const binDir = path.join(moduleRoot, "target", "release")
-  const surface = path.join(binDir, "surface")
-  const insight = path.join(binDir, "insight")
-  const reflect = path.join(binDir, "reflect")
+  const bin = path.join(moduleRoot, "target", "release", "forge-reflect")
 
-  // Ensure binaries are built; skip silently if cargo unavailable
+  // Ensure the binary is built; skip silently if cargo unavailable
   async function ensureBuilt(): Promise<boolean> {
     try {
-      await $`test -x ${surface} && test -x ${insight} && test -x ${reflect}`
+      await $`test -x ${bin}`
       return true
     } catch {
-      // Binaries missing — try building
+      // Binary missing — try building
       try {
         await $`cargo build --release --manifest-path ${path.join(moduleRoot, "Cargo.toml")}`.quiet()
         return true
@@ -34,73 +33,44 @@ export const ForgeReflect: Plugin = async ({ $, directory, worktree }) => {
     }
   }
 
-  // Run a binary with optional stdin, return stdout or empty string
-  async function runBinary(bin: string, stdin?: string): Promise<string> {
+  type Response =
+    | { action: "toast"; level: "info" | "warn"; message: string }
+    | { action: "context"; text: string; notice?: string }
+
+  // Run a subcommand with a JSON payload on stdin, return the parsed response
+  async function run(subcommand: string, payload: object): Promise<Response | null> {
     try {
-      if (stdin) {
-        return await $`echo ${stdin} | FORGE_MODULE_ROOT=${moduleRoot} ${bin}`.text()
-      }
-      return await $`FORGE_MODULE_ROOT=${moduleRoot} ${bin}`.text()
+      const stdout = await $`echo ${JSON.stringify(payload)} | FORGE_MODULE_ROOT=${moduleRoot} ${bin} ${subcommand} --host opencode --format json`.text()
+      return stdout.trim() ? (JSON.parse(stdout.trim()) as Response) : null
     } catch {
-      return ""
+      return null
     }
   }
 
+  function toast(message: string, level: "info" | "warn") {
+    return { type: "tui.toast.show" as const, toast: { message, level } }
+  }
+
   const ready = await ensureBuilt()
 
   return {
-    // SessionStart equivalent: run surface binary, show digest as TUI toast
     event: async ({ event }) => {
-      if (event.type === "session.created" && ready) {
-        const digest = await runBinary(surface)
-        if (digest.trim()) {
-          return {
-            type: "tui.toast.show" as const,
-            toast: { message: digest.trim(), level: "info" as const },
-          }
-        }
-      }
-
-      // Stop equivalent: run insight then reflect on session idle
-      if (event.type === "session.idle" && ready) {
-        const input = JSON.stringify({ cwd: directory, transcript_path: "" })
+      if (!ready) return
 
-        // Insight check first (hard rule)
-        const insightResult = await runBinary(insight, input)
-        if (insightResult.trim()) {
-          try {
-            const parsed = JSON.parse(insightResult.trim())
-            if (parsed.decision === "block") {
-              return {
-                type: "tui.toast.show" as const,
-                toast: {
-                  message: `forge-reflect: ${parsed.reason}`,
-                  level: "warn" as const,
-                },
-              }
-            }
-          } catch {
-            // Not valid JSON, ignore
-          }
+      // SessionStart equivalent: show the surface digest as a TUI toast
+      if (event.type === "session.created") {
+        const response = await run("surface", { cwd: directory })
+        if (response?.action === "context") {
+          const text = response.notice ? `${response.text.trim()}\n${response.notice}` : response.text.trim()
+          return toast(text, response.notice ? "warn" : "info")
         }
+      }
 
-        // Reflect check (soft heuristic)
-        const reflectResult = await runBinary(reflect, input)
-        if (reflectResult.trim()) {
-          try {
-            const parsed = JSON.parse(reflectResult.trim())
-            if (parsed.decision === "block") {
-              return {
-                type: "tui.toast.show" as const,
-                toast: {
-                  message: `forge-reflect: ${parsed.reason}`,
-                  level: "warn" as const,
-                },
-              }
-            }
-          } catch {
-            // Not valid JSON, ignore
-          }
+      // Stop equivalent: insight + reflect gates on session idle
+      if (event.type === "session.idle") {
+        const response = await run("stop", { cwd: directory, transcript_path: "" })
+        if (response?.action === "toast") {
+          return toast(`forge-reflect: ${response.message}`, response.level)
         }
       }
     },
@@ -108,17 +78,9 @@ export const ForgeReflect: Plugin = async ({ $, directory, worktree }) => {
     // PreCompact equivalent: inject reflection context before compaction
     "experimental.session.compacting": async (input, output) => {
       if (!ready) return
-      const payload = JSON.stringify({ cwd: directory, trigger: "auto" })
-      const result = await runBinary(reflect, payload)
-      if (result.trim()) {
-        try {
-          const parsed = JSON.parse(result.trim())
-          if (parsed.additionalContext) {
-            output.context.push(parsed.additionalContext)
-          }
-        } catch {
-          // Not valid JSON, ignore
-        }
+      const response = await run("precompact", { cwd: directory, trigger: "auto" })
+      if (response?.action === "context") {
+        output.context.push(response.text)
       }
     },
   }
@@ -0,0 +1,12 @@
+#!/usr/bin/env bash
+# UserPromptSubmit hook: "remember that ..." prompts become capture requests
+# enforced by the capture Stop gate (phrases and cues: capture.*).
+set -euo pipefail
+
+MODULE_ROOT="${FORGE_MODULE_ROOT:-${CLAUDE_PLUGIN_ROOT:-$(command cd "$(dirname "$0")/.." && pwd)}}"
+export FORGE_MODULE_ROOT="$MODULE_ROOT"
+
+source "$MODULE_ROOT/bin/_build.sh"
+ensure_built forge-reflect || exit 0
+
+exec "$BIN_DIR/forge-reflect" userpromptsubmit
@@ -0,0 +1,127 @@
+//! `PostToolUse` triggers — when to show the surface digest, when to run
+//! the advisory insight check, and whether its result is worth repeating.
+//!
+//! No I/O — the CLI layer persists [`Counters`] between tool calls.
+
+use crate::config::{AdvisoryConfig, Config};
+use serde::{Deserialize, Serialize};
+
+/// Per-session counters carried between `PostToolUse` invocations.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Counters {
+    /// Tool calls since the last interval trigger.
+    pub tool_calls: u32,
+    /// Unix seconds of the first tool call seen, 0 if none yet.
+    pub started_at: i64,
+    /// The one-shot duration trigger has fired.
+    pub duration_fired: bool,
+    /// Named topics in the last advisory.
+    pub advised_topics: Vec<String>,
+    /// Unnamed insight blocks in the last advisory.
+    pub advised_unnamed: usize,
+    /// Unix seconds of the last advisory, 0 if none.
+    pub advised_at: i64,
+    /// Advisories given for the current outstanding set — the ladder rung.
+    pub level: u32,
+}
+
+/// Why an advisory insight check is due.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trigger {
+    Interval,
+    Duration,
+    InsightCount,
+}
+
+impl Trigger {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Interval => "interval",
+            Self::Duration => "duration",
+            Self::InsightCount => "insight count",
+        }
+    }
+}
+
+/// True when `skill` is one of `surface.trigger_skills`.
+pub fn is_surface_trigger(skill: &str, config: &Config) -> bool {
+    !skill.is_empty() && config.surface.trigger_skills.iter().any(|s| s == skill)
+}
+
+/// Count one tool call at `now` (Unix seconds) and decide whether an
+/// advisory check is due. Triggers are tried in order — interval, duration,
+/// insight count — and the first one wins. `insight_count` is only called
+/// on every `insight_check_every`-th call (0 disables), since it reads the
+/// transcript.
+pub fn tick(
+    counters: &mut Counters,
+    now: i64,
+    config: &AdvisoryConfig,
+    insight_count: impl FnOnce() -> usize,
+) -> Option<Trigger> {
+    if counters.started_at == 0 {
+        counters.started_at = now;
+    }
+    counters.tool_calls += 1;
+
+    if counters.tool_calls >= config.interval {
+        counters.tool_calls = 0;
+        return Some(Trigger::Interval);
+    }
+
+    let elapsed = now.saturating_sub(counters.started_at);
+    if !counters.duration_fired && elapsed >= i64::from(config.duration_minutes) * 60 {
+        counters.duration_fired = true;
+        return Some(Trigger::Duration);
+    }
+
+    if counters
+        .tool_calls
+        .is_multiple_of(config.insight_check_every)
+        && insight_count() >= config.insight_threshold
+    {
+        return Some(Trigger::InsightCount);
+    }
+
+    None
+}
+
+/// Decide whether an advisory check that found `topics` (plus `unnamed`
+/// blocks) uncaptured should speak. Returns the escalation level to word it
+/// with — an index into `advisory.levels`, clamped to the last rung — or
+/// None when the set has not grown since the last advisory and the
+/// cool-down has not passed. An empty set resets the ladder.
+pub fn advise(
+    counters: &mut Counters,
+    topics: &[&str],
+    unnamed: usize,
+    now: i64,
+    config: &AdvisoryConfig,
+) -> Option<usize> {
+    if topics.is_empty() && unnamed == 0 {
+        counters.advised_topics.clear();
+        counters.advised_unnamed = 0;
+        counters.level = 0;
+        return None;
+    }
+
+    let grown = unnamed > counters.advised_unnamed
+        || topics
+            .iter()
+            .any(|t| !counters.advised_topics.iter().any(|a| a == t));
+    let cooled = now.saturating_sub(counters.advised_at) >= i64::from(config.cooldown_minutes) * 60;
+    if counters.advised_at != 0 && !grown && !cooled {
+        return None;
+    }
+
+    let level = (counters.level as usize).min(config.levels.len().saturating_sub(1));
+    counters.level += 1;
+    counters.advised_topics = topics.iter().map(ToString::to_string).collect();
+    counters.advised_unnamed = unnamed;
+    counters.advised_at = now;
+    Some(level)
+}
+
+#[cfg(test)]
+mod tests;
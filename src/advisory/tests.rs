@@ -0,0 +1,104 @@
+use super::*;
+
+fn counters(tool_calls: u32, started_at: i64) -> Counters {
+    Counters {
+        tool_calls,
+        started_at,
+        duration_fired: false,
+        ..Counters::default()
+    }
+}
+
+#[test]
+fn test_first_call_records_start() {
+    let mut c = Counters::default();
+    assert_eq!(tick(&mut c, 1000, &AdvisoryConfig::default(), || 0), None);
+    assert_eq!(c.started_at, 1000);
+    assert_eq!(c.tool_calls, 1);
+}
+
+#[test]
+fn test_interval_fires_and_resets() {
+    let mut c = counters(24, 1000);
+    let trigger = tick(&mut c, 1001, &AdvisoryConfig::default(), || 0);
+    assert_eq!(trigger, Some(Trigger::Interval));
+    assert_eq!(c.tool_calls, 0);
+}
+
+#[test]
+fn test_duration_fires_once() {
+    let config = AdvisoryConfig::default();
+    let mut c = counters(0, 1000);
+    let later = 1000 + 20 * 60;
+    assert_eq!(tick(&mut c, later, &config, || 0), Some(Trigger::Duration));
+    assert!(c.duration_fired);
+    assert_eq!(tick(&mut c, later + 60, &config, || 0), None);
+}
+
+#[test]
+fn test_insight_count_checked_only_on_multiples() {
+    let config = AdvisoryConfig::default();
+    let mut c = counters(3, 1000);
+    let trigger = tick(&mut c, 1001, &config, || {
+        panic!("transcript read off-cycle")
+    });
+    assert_eq!(trigger, None);
+
+    let mut c = counters(9, 1000);
+    assert_eq!(
+        tick(&mut c, 1001, &config, || 10),
+        Some(Trigger::InsightCount)
+    );
+    let mut c = counters(9, 1000);
+    assert_eq!(tick(&mut c, 1001, &config, || 9), None);
+}
+
+#[test]
+fn test_interval_wins_over_other_triggers() {
+    let config = AdvisoryConfig {
+        interval: 10,
+        ..AdvisoryConfig::default()
+    };
+    let mut c = counters(9, 1000);
+    let trigger = tick(&mut c, 1000 + 3600, &config, || 50);
+    assert_eq!(trigger, Some(Trigger::Interval));
+    assert!(!c.duration_fired);
+}
+
+#[test]
+fn test_surface_trigger_skills() {
+    let config = Config::default();
+    assert!(is_surface_trigger("DailyPlan", &config));
+    assert!(!is_surface_trigger("Commit", &config));
+    assert!(!is_surface_trigger("", &config));
+}
+
+#[test]
+fn test_advise_repeats_only_when_set_grows() {
+    let config = AdvisoryConfig::default();
+    let mut c = Counters::default();
+    assert_eq!(advise(&mut c, &["A"], 0, 1000, &config), Some(0));
+    assert_eq!(advise(&mut c, &["A"], 0, 1060, &config), None);
+    assert_eq!(advise(&mut c, &["A", "B"], 0, 1120, &config), Some(1));
+    assert_eq!(advise(&mut c, &["A", "B"], 1, 1180, &config), Some(1));
+}
+
+#[test]
+fn test_advise_repeats_after_cooldown() {
+    let config = AdvisoryConfig::default();
+    let mut c = Counters::default();
+    assert_eq!(advise(&mut c, &["A"], 0, 1000, &config), Some(0));
+    assert_eq!(advise(&mut c, &["A"], 0, 1000 + 29 * 60, &config), None);
+    assert_eq!(advise(&mut c, &["A"], 0, 1000 + 30 * 60, &config), Some(1));
+}
+
+#[test]
+fn test_advise_resets_when_all_captured() {
+    let config = AdvisoryConfig::default();
+    let mut c = Counters::default();
+    advise(&mut c, &["A"], 0, 1000, &config);
+    advise(&mut c, &["A", "B"], 0, 1060, &config);
+    assert_eq!(advise(&mut c, &[], 0, 1120, &config), None);
+    assert_eq!(c.level, 0);
+    assert_eq!(advise(&mut c, &["C"], 0, 1180, &config), Some(0));
+}
@@ -0,0 +1,143 @@
+//! "Remember this" requests — capture phrases in user prompts, classified by
+//! the memory directory the note belongs in, and matched against later writes.
+//!
+//! No I/O — the CLI layer stores [`Request`]s in the session state and counts
+//! writes from the transcript.
+
+use crate::config::{CaptureConfig, MemoryConfig};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Memory type a request is filed under.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Kind {
+    Insight,
+    Imperative,
+    Idea,
+}
+
+impl Kind {
+    pub const ALL: [Self; 3] = [Self::Insight, Self::Imperative, Self::Idea];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Insight => "insight",
+            Self::Imperative => "imperative",
+            Self::Idea => "idea",
+        }
+    }
+
+    /// User-root-relative directory for this kind.
+    pub fn dir(self, memory: &MemoryConfig) -> &str {
+        match self {
+            Self::Insight => &memory.insights,
+            Self::Imperative => &memory.imperatives,
+            Self::Idea => &memory.ideas,
+        }
+    }
+}
+
+/// A capture request recorded at `UserPromptSubmit`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Request {
+    pub kind: Kind,
+    pub text: String,
+    /// Unix seconds.
+    pub requested_at: i64,
+    /// Writes to the kind's directory already in the transcript when the
+    /// request was made — only later writes fulfil it.
+    pub writes_before: usize,
+}
+
+/// Longest request text kept; the rest of a long prompt is context, not the note.
+const MAX_TEXT: usize = 200;
+
+/// The capture request in `prompt`, if a configured phrase occurs. The text
+/// is the rest of the phrase's line, or the whole prompt when the phrase
+/// ends it ("Tests need --release. Remember this.").
+pub fn detect(prompt: &str, config: &CaptureConfig) -> Option<(Kind, String)> {
+    let (_, end) = config
+        .phrases
+        .iter()
+        .filter_map(|phrase| find_word(prompt, phrase))
+        .min_by_key(|&(start, _)| start)?;
+    let rest = prompt[end..]
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .trim_start_matches(|c: char| c.is_whitespace() || ":,-\u{2014}".contains(c))
+        .trim_end_matches(|c: char| c.is_whitespace() || c == '.');
+    let text = if rest.is_empty() { prompt.trim() } else { rest };
+    let text = truncate(text, MAX_TEXT);
+    Some((classify(&text, config), text))
+}
+
+/// Imperative when an imperative cue occurs, else idea on an idea cue, else
+/// insight.
+pub fn classify(text: &str, config: &CaptureConfig) -> Kind {
+    let has = |cues: &[String]| cues.iter().any(|cue| find_word(text, cue).is_some());
+    if has(&config.imperative_cues) {
+        Kind::Imperative
+    } else if has(&config.idea_cues) {
+        Kind::Idea
+    } else {
+        Kind::Insight
+    }
+}
+
+/// `#tags` in a request's text, without the `#` — they route the note to a
+/// vault (see [`crate::config::UserRoot`]).
+pub fn tags(text: &str) -> Vec<&str> {
+    text.split_whitespace()
+        .filter_map(|word| word.strip_prefix('#'))
+        .map(|tag| tag.trim_end_matches(|c: char| !c.is_alphanumeric()))
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
+
+/// Requests not yet fulfilled, given the current write count per kind. Each
+/// write after a request's `writes_before` fulfils one request of that kind,
+/// oldest first.
+pub fn pending(requests: &[Request], writes: impl Fn(Kind) -> usize) -> Vec<&Request> {
+    let mut credited: HashMap<Kind, usize> = HashMap::new();
+    requests
+        .iter()
+        .filter(|request| {
+            let cursor = credited.entry(request.kind).or_default();
+            *cursor = (*cursor).max(request.writes_before);
+            if writes(request.kind) > *cursor {
+                *cursor += 1;
+                false
+            } else {
+                true
+            }
+        })
+        .collect()
+}
+
+/// Byte range of `word` in `text`, case-insensitive, not inside a longer word.
+fn find_word(text: &str, word: &str) -> Option<(usize, usize)> {
+    if word.trim().is_empty() {
+        return None;
+    }
+    let re = regex::Regex::new(&format!(
+        r"(?i)(?:^|[^\w'])({})(?:$|[^\w'])",
+        regex::escape(word)
+    ))
+    .ok()?;
+    let found = re.captures(text)?.get(1)?;
+    Some((found.start(), found.end()))
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let cut: String = text.chars().take(max).collect();
+    format!("{}\u{2026}", cut.trim_end())
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,90 @@
+use super::*;
+
+fn cfg() -> CaptureConfig {
+    CaptureConfig::default()
+}
+
+fn request(kind: Kind, writes_before: usize) -> Request {
+    Request {
+        kind,
+        text: String::new(),
+        requested_at: 0,
+        writes_before,
+    }
+}
+
+#[test]
+fn test_detect_takes_rest_of_line() {
+    let (kind, text) = detect(
+        "Remember that the staging DB resets nightly.\nNow fix the test.",
+        &cfg(),
+    )
+    .unwrap();
+    assert_eq!(kind, Kind::Insight);
+    assert_eq!(text, "the staging DB resets nightly");
+}
+
+#[test]
+fn test_detect_trailing_phrase_uses_whole_prompt() {
+    let (_, text) = detect("Tests need --release. Remember this.", &cfg()).unwrap();
+    assert_eq!(text, "Tests need --release. Remember this.");
+}
+
+#[test]
+fn test_detect_ignores_phrase_inside_word_and_plain_prompts() {
+    assert!(detect("Fix the misremember that bug", &cfg()).is_none());
+    assert!(detect("Refactor the config loader", &cfg()).is_none());
+}
+
+#[test]
+fn test_classify_cues() {
+    let config = cfg();
+    assert_eq!(
+        classify("always run cargo fmt before committing", &config),
+        Kind::Imperative
+    );
+    assert_eq!(
+        classify("what if surface learned from skips", &config),
+        Kind::Idea
+    );
+    assert_eq!(
+        classify("the ledger is cleared by surface", &config),
+        Kind::Insight
+    );
+    // "idea" only as a whole word
+    assert_eq!(
+        classify("ideally the cache is warm", &config),
+        Kind::Insight
+    );
+}
+
+#[test]
+fn test_tags() {
+    assert_eq!(
+        tags("deploys need the #team-ops runbook, #infra."),
+        vec!["team-ops", "infra"]
+    );
+    assert!(tags("issue # 12 and C#").is_empty());
+}
+
+#[test]
+fn test_pending_credits_each_write_once_in_order() {
+    let requests = [
+        request(Kind::Insight, 0),
+        request(Kind::Insight, 0),
+        request(Kind::Idea, 2),
+    ];
+    let writes = |insights: usize, ideas: usize| {
+        move |kind: Kind| match kind {
+            Kind::Insight => insights,
+            Kind::Idea => ideas,
+            Kind::Imperative => 0,
+        }
+    };
+    assert_eq!(pending(&requests, writes(0, 2)).len(), 3);
+    assert_eq!(
+        pending(&requests, writes(1, 2)),
+        vec![&requests[1], &requests[2]]
+    );
+    assert_eq!(pending(&requests, writes(2, 3)).len(), 0);
+}
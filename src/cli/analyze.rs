@@ -0,0 +1,58 @@
+use super::Format;
+use crate::config::Config;
+use crate::gate;
+use crate::transcript;
+use crate::HookInput;
+use std::fs;
+use std::process::ExitCode;
+
+/// Print the transcript analysis and gate inputs. Exits 1 if the transcript is unreadable.
+pub(super) fn run(config: &Config, input: &HookInput, format: Format) -> ExitCode {
+    let Ok(transcript) = fs::read_to_string(&input.transcript_path) else {
+        eprintln!(
+            "forge-reflect[analyze]: transcript unreadable at '{}'",
+            input.transcript_path
+        );
+        return ExitCode::FAILURE;
+    };
+
+    let analysis = transcript::analyze_transcript(&transcript, config);
+    let uncaptured = gate::uncaptured_insights(&analysis);
+    let insight_substantial = gate::insight_substantial(&analysis, config);
+    let reflect_substantial = gate::reflect_substantial(&analysis, config);
+
+    match format {
+        Format::Json => {
+            let output = serde_json::json!({
+                "analysis": analysis,
+                "uncaptured": uncaptured,
+                "insight_substantial": insight_substantial,
+                "reflect_substantial": reflect_substantial,
+            });
+            println!("{output}");
+        }
+        Format::Text => {
+            println!("user messages:       {}", analysis.user_messages);
+            println!("tool-using turns:    {}", analysis.tool_using_turns);
+            println!("duration (min):      {}", analysis.session_duration_minutes);
+            println!("memory write:        {}", analysis.has_memory_write);
+            println!(
+                "insights:            {} ({} written)",
+                analysis.insight_count, analysis.insights_write_count
+            );
+            println!("insight substantial: {insight_substantial}");
+            println!("reflect substantial: {reflect_substantial}");
+            if uncaptured.is_empty() {
+                println!("uncaptured:          none");
+            } else {
+                println!(
+                    "uncaptured:          {} ({})",
+                    uncaptured.total(),
+                    uncaptured.topics_display()
+                );
+            }
+        }
+    }
+
+    ExitCode::SUCCESS
+}
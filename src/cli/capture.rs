@@ -0,0 +1,126 @@
+use super::{enforced, message_vars, session_id, state_store, Outcome, Output};
+use crate::capture::{self, Request};
+use crate::config::Config;
+use crate::explain::Trace;
+use crate::gate;
+use crate::prompt::template;
+use crate::transcript::{self, TranscriptAnalysis};
+use crate::HookInput;
+use std::fmt::Write;
+use std::fs;
+use std::process::ExitCode;
+
+/// `UserPromptSubmit`: when the prompt asks to remember something, tell the
+/// model where the memory file goes and record the request for the `capture`
+/// Stop gate.
+pub(super) fn run_prompt(
+    config: &Config,
+    input: &HookInput,
+    out: Output,
+    trace: &mut Trace,
+) -> ExitCode {
+    if !enforced("capture", config, input, trace) {
+        return ExitCode::SUCCESS;
+    }
+    let Some((kind, text)) = capture::detect(&input.prompt, &config.capture) else {
+        trace.decide("allow \u{2014} no capture phrase");
+        return ExitCode::SUCCESS;
+    };
+    trace.step("request", format!("{}: {text}", kind.name()));
+
+    // Writes already in the transcript don't count toward this request.
+    let analysis = fs::read_to_string(&input.transcript_path)
+        .ok()
+        .map(|t| transcript::analyze_transcript(&t, config));
+    let writes_before = analysis.as_ref().map_or(0, |analysis| {
+        gate::capture_writes(kind, config, &input.cwd, &analysis.file_writes)
+    });
+    let request = Request {
+        kind,
+        text: text.clone(),
+        requested_at: chrono::Utc::now().timestamp(),
+        writes_before,
+    };
+    let now = request.requested_at;
+    if let Err(e) = state_store(config).update(&session_id(input), now, |state| {
+        state.capture_requests.push(request);
+    }) {
+        eprintln!("forge-reflect[capture]: state update failed: {e}");
+    }
+
+    let dir = config.resolve_memory_dir(&input.cwd, kind, &capture::tags(&text));
+    let vars = message_vars(config, &input.cwd, analysis.as_ref())
+        .with("kind", kind.name())
+        .with("text", &text)
+        .with("dir", dir.display());
+    let context = template::render(&config.capture.prompt, &vars);
+    out.context("UserPromptSubmit", &context);
+    eprintln!("forge-reflect[capture]: {} request recorded", kind.name());
+    trace.decide(format!("inject \u{2014} {} capture request", kind.name()));
+    ExitCode::SUCCESS
+}
+
+/// Stop gate: every request recorded this session has a later write in its
+/// kind's memory directory. Explicit requests skip the substantiality check.
+pub(super) fn evaluate(
+    config: &Config,
+    input: &HookInput,
+    analysis: &TranscriptAnalysis,
+    trace: &mut Trace,
+) -> Outcome {
+    let requests = state_store(config)
+        .load(&session_id(input))
+        .capture_requests;
+    if requests.is_empty() {
+        return Outcome::Allow("allow \u{2014} no capture requests".to_string());
+    }
+    let pending = gate::pending_captures(&requests, analysis, config, &input.cwd);
+    trace.step(
+        "capture requests",
+        format!("{} recorded, {} pending", requests.len(), pending.len()),
+    );
+    if pending.is_empty() {
+        return Outcome::Allow(format!(
+            "allow \u{2014} {} capture request(s) fulfilled",
+            requests.len()
+        ));
+    }
+
+    let mut list = String::new();
+    for request in &pending {
+        let dir =
+            config.resolve_memory_dir(&input.cwd, request.kind, &capture::tags(&request.text));
+        let _ = write!(
+            list,
+            "\n- {} \u{2192} {}: {}",
+            request.kind.name(),
+            dir.display(),
+            request.text
+        );
+    }
+    let count = pending.len();
+    if !gate::capture_blocks(&requests, analysis, config, &input.cwd) {
+        eprintln!("forge-reflect[capture]: warn \u{2014} {count} unfulfilled capture request(s)");
+        return Outcome::Allow(format!(
+            "warn \u{2014} {count} capture request(s) without a file (profile {})",
+            config.profile.name()
+        ));
+    }
+    eprintln!("forge-reflect[capture]: blocking \u{2014} {count} unfulfilled capture request(s)");
+    Outcome::Block {
+        reason: template::render(
+            &config.capture.reason,
+            &message_vars(config, &input.cwd, Some(analysis))
+                .with("count", count)
+                .with("requests", list.trim_start_matches('\n')),
+        ),
+        summary: format!(
+            "{count} capture request(s) without a file: {}",
+            pending
+                .iter()
+                .map(|r| r.text.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
@@ -0,0 +1,60 @@
+use super::{ConfigCommand, Format};
+use crate::config::{validate, Config};
+use std::io::{self, Write};
+use std::process::ExitCode;
+
+pub(super) fn run(config: &Config, command: &ConfigCommand, format: Format) -> ExitCode {
+    match command {
+        ConfigCommand::Get { key } => get(config, key, format),
+        ConfigCommand::Schema => schema(),
+    }
+}
+
+/// Exits 1 for an unknown key, suggesting the closest one. A reader that
+/// closes the pipe early (`| head -1`) is not an error.
+fn get(config: &Config, key: &str, format: Format) -> ExitCode {
+    let Some(value) = config.get(key) else {
+        let known = serde_yaml::to_value(config).unwrap_or_default();
+        match validate::suggest(key, &known) {
+            Some(suggestion) => {
+                eprintln!("forge-reflect: unknown key `{key}` (did you mean `{suggestion}`?)");
+            }
+            None => eprintln!("forge-reflect: unknown key `{key}`"),
+        }
+        return ExitCode::FAILURE;
+    };
+
+    let text = match (&value, format) {
+        (_, Format::Json) => serde_json::to_string(&value).map_err(|e| e.to_string()),
+        (serde_yaml::Value::String(s), Format::Text) => Ok(s.clone()),
+        (serde_yaml::Value::Null, Format::Text) => Ok(String::new()),
+        (_, Format::Text) => serde_yaml::to_string(&value)
+            .map(|s| s.trim_end().to_string())
+            .map_err(|e| e.to_string()),
+    };
+    let written = text.and_then(|text| match writeln!(io::stdout().lock(), "{text}") {
+        Err(e) if e.kind() != io::ErrorKind::BrokenPipe => Err(e.to_string()),
+        _ => Ok(()),
+    });
+    match written {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("forge-reflect: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Always JSON: the schema is the same whatever layers are loaded.
+fn schema() -> ExitCode {
+    match serde_json::to_string_pretty(&Config::schema()) {
+        Ok(text) => {
+            println!("{text}");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("forge-reflect: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
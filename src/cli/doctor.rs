@@ -0,0 +1,26 @@
+use super::Format;
+use crate::config::Config;
+use crate::doctor::{self, Status};
+use std::path::Path;
+use std::process::ExitCode;
+
+/// Validate the installation. Exits 1 if any check fails; warnings pass.
+pub(super) fn run(
+    config: &Config,
+    config_file: Option<&Path>,
+    cwd: &str,
+    format: Format,
+) -> ExitCode {
+    let checks = doctor::run_checks(config, config_file, cwd);
+
+    match format {
+        Format::Json => println!("{}", serde_json::json!({ "checks": checks })),
+        Format::Text => print!("{}", doctor::render_text(&checks)),
+    }
+
+    if checks.iter().any(|c| c.status == Status::Fail) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
@@ -0,0 +1,31 @@
+use super::HooksArgs;
+use crate::hooks;
+use crate::response::Host;
+use std::process::ExitCode;
+
+pub(super) fn run(args: &HooksArgs) -> ExitCode {
+    let bin = std::env::current_exe().map_or_else(
+        |_| "forge-reflect".to_string(),
+        |p| p.to_string_lossy().into_owned(),
+    );
+
+    match args.target {
+        Host::Gemini => match serde_json::to_string_pretty(&hooks::gemini_settings(&bin)) {
+            Ok(json) => println!("{json}"),
+            Err(e) => {
+                eprintln!("forge-reflect: {e}");
+                return ExitCode::FAILURE;
+            }
+        },
+        Host::Codex => println!("{}", hooks::codex_notify(&bin)),
+        other => {
+            eprintln!(
+                "forge-reflect: no hook generator for {} \u{2014} Claude Code uses hooks/hooks.json, \
+                 opencode uses .opencode/plugins/forge-reflect.ts",
+                other.name()
+            );
+            return ExitCode::FAILURE;
+        }
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,151 @@
+use super::{
+    honour_override, message_vars, session_id, state_store, stop_analysis, Outcome, Output,
+};
+use crate::advisory;
+use crate::config::Config;
+use crate::explain::Trace;
+use crate::gate;
+use crate::prompt::template;
+use crate::transcript::TranscriptAnalysis;
+use crate::HookInput;
+use std::process::ExitCode;
+
+pub(super) fn run(
+    config: &Config,
+    input: &HookInput,
+    out: Output,
+    advisory: bool,
+    trace: &mut Trace,
+) -> ExitCode {
+    let Some(analysis) = stop_analysis("insight", config, input, trace) else {
+        return ExitCode::SUCCESS;
+    };
+
+    trace.step("mode", if advisory { "advisory" } else { "stop" });
+
+    // In advisory mode, skip the substantiality gate — nudging is low-cost,
+    // we want early detection.
+    if advisory {
+        run_advisory(config, input, out, &analysis, trace);
+        return ExitCode::SUCCESS;
+    }
+
+    match evaluate(config, input, &analysis, trace) {
+        Outcome::Allow(decision) => trace.decide(decision),
+        Outcome::Block { reason, summary } => {
+            let bypassed = vec![format!("insight: {summary}")];
+            if !honour_override("insight", config, input, &analysis, bypassed, trace) {
+                out.block(&reason);
+                trace.decide(format!("block \u{2014} {summary}"));
+            }
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+/// Advise once per uncaptured set: repeat only when it grows or the
+/// cool-down passes, wording each repeat one rung up `advisory.levels`.
+fn run_advisory(
+    config: &Config,
+    input: &HookInput,
+    out: Output,
+    analysis: &TranscriptAnalysis,
+    trace: &mut Trace,
+) {
+    let uncaptured = gate::uncaptured_insights(analysis);
+    let now = chrono::Utc::now().timestamp();
+    let level = state_store(config)
+        .update(&session_id(input), now, |state| {
+            advisory::advise(
+                &mut state.advisory,
+                &uncaptured.topics,
+                uncaptured.unnamed,
+                now,
+                &config.advisory,
+            )
+        })
+        .unwrap_or_else(|e| {
+            // Without state every check would repeat; fall back to the first rung.
+            eprintln!("forge-reflect[insight]: state update failed: {e}");
+            (!uncaptured.is_empty()).then_some(0)
+        });
+
+    if uncaptured.is_empty() {
+        trace.decide("allow \u{2014} all insights captured");
+        return;
+    }
+    let total = uncaptured.total();
+    let Some(level) = level else {
+        trace.decide(format!(
+            "allow \u{2014} {total} uncaptured insight(s) already advised, cooling down"
+        ));
+        return;
+    };
+    let template = config
+        .advisory
+        .levels
+        .get(level)
+        .unwrap_or(&config.insight_advisory_prompt);
+    let msg = template::render(template, &message_vars(config, &input.cwd, Some(analysis)));
+    out.context("PostToolUse", &msg);
+    trace.decide(format!(
+        "advise (level {}) \u{2014} {total} uncaptured insight(s){}",
+        level + 1,
+        uncaptured.detail()
+    ));
+}
+
+/// Hard rule: a substantial session (mirrors the reflect thresholds) with
+/// uncaptured insights blocks, or warns when the profile does not block.
+pub(super) fn evaluate(
+    config: &Config,
+    input: &HookInput,
+    analysis: &TranscriptAnalysis,
+    trace: &mut Trace,
+) -> Outcome {
+    trace.threshold(
+        "user messages",
+        analysis.user_messages as u64,
+        config.user_msg_threshold as u64,
+    );
+    trace.threshold(
+        "tool turns",
+        analysis.tool_using_turns as u64,
+        config.tool_turn_threshold as u64,
+    );
+    if !gate::insight_substantial(analysis, config) {
+        eprintln!(
+            "forge-reflect[insight]: session not substantial ({} msgs, {} tool turns), allowing",
+            analysis.user_messages, analysis.tool_using_turns
+        );
+        return Outcome::Allow("allow \u{2014} session not substantial".to_string());
+    }
+
+    let uncaptured = gate::uncaptured_insights(analysis);
+    if uncaptured.is_empty() {
+        return Outcome::Allow("allow \u{2014} all insights captured".to_string());
+    }
+    let total = uncaptured.total();
+    let reason_detail = uncaptured.detail();
+
+    if gate::insight_blocks(analysis, config) {
+        eprintln!(
+            "forge-reflect[insight]: blocking \u{2014} {total} uncaptured insight(s){reason_detail}"
+        );
+        Outcome::Block {
+            reason: template::render(
+                &config.uncaptured_insight_reason,
+                &message_vars(config, &input.cwd, Some(analysis)),
+            ),
+            summary: format!("{total} uncaptured insight(s){reason_detail}"),
+        }
+    } else {
+        eprintln!(
+            "forge-reflect[insight]: warn \u{2014} {total} uncaptured insight(s){reason_detail}"
+        );
+        Outcome::Allow(format!(
+            "warn \u{2014} {total} uncaptured insight(s){reason_detail} (profile {})",
+            config.profile.name()
+        ))
+    }
+}
@@ -0,0 +1,714 @@
+//! Command-line interface — one `forge-reflect` binary with subcommands.
+//!
+//! The legacy `surface`, `insight` and `reflect` binaries are thin aliases:
+//! they prepend their subcommand name and delegate to [`run_alias`].
+
+mod analyze;
+mod capture;
+mod config;
+mod doctor;
+mod hooks;
+mod insight;
+mod notify;
+mod posttooluse;
+mod reflect;
+mod replay;
+mod stop;
+mod surface;
+
+use crate::capture::Kind;
+use crate::config::Config;
+use crate::explain::Trace;
+use crate::gate;
+use crate::prompt::template::Vars;
+use crate::response::{HookResponse, Host};
+use crate::state::{AuditRecord, OverrideRecord, Store};
+use crate::transcript::{self, TranscriptAnalysis};
+use crate::HookInput;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::cell::Cell;
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+/// Session reflection — surface digest, insight capture and reflection gates.
+#[derive(Parser, Debug)]
+#[command(name = "forge-reflect", author, version, about, long_about = None)]
+#[command(propagate_version = true)]
+pub struct Cli {
+    #[command(flatten)]
+    pub global: GlobalArgs,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+/// Flags shared by every subcommand.
+#[derive(Args, Debug, Default)]
+pub struct GlobalArgs {
+    /// Config file overlaid on module `defaults.yaml` (replaces `config.yaml`).
+    #[arg(long, global = true)]
+    pub config: Option<PathBuf>,
+
+    /// Output format. Hook commands default to `json`; `surface`, `analyze`
+    /// and `doctor` default to `text`.
+    #[arg(long, global = true, value_enum)]
+    pub format: Option<Format>,
+
+    /// Hook protocol to speak. Detected from the payload when omitted.
+    #[arg(long, global = true, value_enum)]
+    pub host: Option<Host>,
+
+    /// Current working directory (defaults to the process cwd).
+    #[arg(long, global = true)]
+    pub cwd: Option<String>,
+
+    /// Path to session transcript JSONL.
+    #[arg(long, global = true, visible_alias = "transcript-path")]
+    pub transcript: Option<String>,
+
+    /// Print a decision trace to stderr: config layers, thresholds, topic
+    /// evidence, credited writes and the final decision. Text unless
+    /// `--format json` is given explicitly.
+    #[arg(long, global = true)]
+    pub explain: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Hook protocol JSON on stdout (empty = allow).
+    Json,
+    /// Human-readable text.
+    Text,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Session digest: stale ideas and a rediscovery pool.
+    Surface,
+    /// Hard rule: block when ★ Insight blocks have no matching file.
+    Insight(InsightArgs),
+    /// Soft heuristic: block a substantial session with no memory writes.
+    Reflect(ReflectArgs),
+    /// Stop hook: analyze once, apply the gates in `stop_gates` order and
+    /// merge their reasons into one block.
+    Stop(StopArgs),
+    /// Inject the reflection prompt before context compaction.
+    Precompact(PrecompactArgs),
+    /// Codex `notify` program: after each agent turn, record what the Stop
+    /// gates would block on as a pending reflection.
+    Notify(NotifyArgs),
+    /// `PostToolUse` hook: one-shot surface digest on the first journals
+    /// skill, then periodic advisory insight checks.
+    #[command(name = "posttooluse")]
+    PostToolUse,
+    /// `UserPromptSubmit` hook: turn "remember that ..." into a capture
+    /// request the `capture` Stop gate enforces.
+    #[command(name = "userpromptsubmit")]
+    UserPromptSubmit,
+    /// Print transcript analysis counts and uncaptured topics.
+    Analyze,
+    /// Validate the installation: module root, config files, vault paths,
+    /// skill files, markers and hook registration.
+    Doctor,
+    /// Run the Stop gates over a directory of historical transcripts.
+    Replay(ReplayArgs),
+    /// Print hook registration for a host without a checked-in config
+    /// (currently Gemini CLI `settings.json`).
+    Hooks(HooksArgs),
+    /// Read the effective config — the same layers and precedence the
+    /// hooks use — for scripts and skills.
+    Config(ConfigArgs),
+}
+
+#[derive(Args, Debug, Default)]
+pub struct StopArgs {
+    /// True when a Stop hook previously blocked in this cycle.
+    #[arg(long)]
+    pub stop_hook_active: bool,
+}
+
+#[derive(Args, Debug, Default)]
+pub struct InsightArgs {
+    #[command(flatten)]
+    pub stop: StopArgs,
+
+    /// Nudge via `PostToolUse` context instead of blocking, skipping the
+    /// substantiality gate.
+    #[arg(long)]
+    pub advisory: bool,
+}
+
+#[derive(Args, Debug, Default)]
+pub struct ReflectArgs {
+    #[command(flatten)]
+    pub stop: StopArgs,
+
+    /// Legacy `PreCompact` routing — when present, behaves like `precompact`.
+    #[arg(long)]
+    pub trigger: Option<String>,
+}
+
+#[derive(Args, Debug, Default)]
+pub struct ReplayArgs {
+    /// Transcript directories (searched recursively for `*.jsonl`) or files.
+    #[arg(required = true)]
+    pub dirs: Vec<PathBuf>,
+
+    /// Candidate config file to compare outcomes against (overlay on `defaults.yaml`).
+    #[arg(long)]
+    pub compare: Option<PathBuf>,
+
+    /// Number of most common uncaptured topics to report.
+    #[arg(long, default_value_t = 10)]
+    pub top: usize,
+}
+
+#[derive(Args, Debug, Default)]
+pub struct NotifyArgs {
+    /// Notification JSON — Codex appends it as the last argument. Read from
+    /// stdin when omitted.
+    pub payload: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct HooksArgs {
+    /// Host to generate the hook config for.
+    #[arg(value_enum)]
+    pub target: Host,
+}
+
+#[derive(Args, Debug)]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    pub command: ConfigCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommand {
+    /// Print the value at a dotted key (`promote.steering`,
+    /// `surface.max_items`). Scalars print bare; groups and lists as YAML,
+    /// or JSON with `--format json`.
+    Get {
+        /// Dotted key path.
+        key: String,
+    },
+    /// Print the JSON Schema of config files, for editor completion and
+    /// validation (`config.schema.json` in the module root).
+    Schema,
+}
+
+#[derive(Args, Debug, Default)]
+pub struct PrecompactArgs {
+    /// "manual" or "auto".
+    #[arg(long)]
+    pub trigger: Option<String>,
+}
+
+impl Command {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Surface => "surface",
+            Self::Insight(_) => "insight",
+            Self::Reflect(_) => "reflect",
+            Self::Stop(_) => "stop",
+            Self::Precompact(_) => "precompact",
+            Self::Notify(_) => "notify",
+            Self::PostToolUse => "posttooluse",
+            Self::UserPromptSubmit => "userpromptsubmit",
+            Self::Analyze => "analyze",
+            Self::Doctor => "doctor",
+            Self::Replay(_) => "replay",
+            Self::Hooks(_) => "hooks",
+            Self::Config(_) => "config",
+        }
+    }
+
+    fn default_format(&self) -> Format {
+        match self {
+            Self::Surface | Self::Analyze | Self::Doctor | Self::Replay(_) | Self::Config(_) => {
+                Format::Text
+            }
+            Self::Insight(_)
+            | Self::Reflect(_)
+            | Self::Stop(_)
+            | Self::Precompact(_)
+            | Self::Notify(_)
+            | Self::PostToolUse
+            | Self::UserPromptSubmit
+            | Self::Hooks(_) => Format::Json,
+        }
+    }
+
+    /// True when a command-specific hook input flag was given on the command line.
+    fn has_input_flags(&self) -> bool {
+        match self {
+            Self::Insight(args) => args.stop.stop_hook_active,
+            Self::Reflect(args) => args.stop.stop_hook_active || args.trigger.is_some(),
+            Self::Stop(args) => args.stop_hook_active,
+            Self::Precompact(args) => args.trigger.is_some(),
+            Self::Notify(args) => args.payload.is_some(),
+            Self::Surface
+            | Self::PostToolUse
+            | Self::UserPromptSubmit
+            | Self::Analyze
+            | Self::Doctor
+            | Self::Replay(_)
+            | Self::Hooks(_)
+            | Self::Config(_) => false,
+        }
+    }
+}
+
+/// Entry point for the `forge-reflect` binary.
+pub fn main() -> ExitCode {
+    run(&Cli::parse())
+}
+
+/// Entry point for the legacy single-purpose binaries: parses
+/// `<binary> [args]` as `forge-reflect <subcommand> [args]`.
+pub fn run_alias(subcommand: &str) -> ExitCode {
+    let argv = [OsString::from("forge-reflect"), OsString::from(subcommand)]
+        .into_iter()
+        .chain(std::env::args_os().skip(1));
+    run(&Cli::parse_from(argv))
+}
+
+/// Hook contract: exit 0 always for hook commands. Block/allow is
+/// communicated via JSON on stdout — empty stdout = allow.
+pub fn run(cli: &Cli) -> ExitCode {
+    let config_file = cli.global.config.as_deref();
+    let format = cli
+        .global
+        .format
+        .unwrap_or_else(|| cli.command.default_format());
+
+    let process_cwd = || {
+        cli.global.cwd.clone().unwrap_or_else(|| {
+            std::env::current_dir()
+                .map(|p| p.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        })
+    };
+    match &cli.command {
+        Command::Doctor => {
+            let cwd = process_cwd();
+            let config = Config::load_with(config_file, &cwd);
+            return doctor::run(&config, config_file, &cwd, format);
+        }
+        Command::Config(args) => {
+            let config = Config::load_with(config_file, &process_cwd());
+            return config::run(&config, &args.command, format);
+        }
+        Command::Replay(args) => {
+            // Transcripts come from many projects — no cwd overlays.
+            return replay::run(&Config::load_with(config_file, ""), args, format);
+        }
+        Command::Hooks(args) => return hooks::run(args),
+        _ => {}
+    }
+
+    let Some(input) = hook_input(&cli.global, &cli.command) else {
+        return ExitCode::SUCCESS;
+    };
+    // Project overlays follow the session's cwd, which may only be known
+    // from the payload.
+    let config = Config::load_with(config_file, &input.cwd);
+
+    let notice = (format == Format::Json && !matches!(cli.command, Command::Analyze))
+        .then(|| config_notice(&config, &input))
+        .flatten();
+    let audit = config.profile.audits().then(|| Audit {
+        store: state_store(&config),
+        session_id: session_id(&input),
+        cwd: input.cwd.clone(),
+        command: cli.command.name(),
+    });
+    let out = Output {
+        format,
+        host: cli.global.host.unwrap_or_else(|| Host::detect(&input)),
+        notice: notice.as_ref(),
+        audit: audit.as_ref(),
+    };
+    let mut trace = Trace::new(cli.command.name(), &config);
+    let code = match &cli.command {
+        Command::Surface => surface::run(&config, &input, out),
+        Command::Insight(args) => insight::run(&config, &input, out, args.advisory, &mut trace),
+        Command::Reflect(_) if input.trigger.is_none() => {
+            reflect::run(&config, &input, out, &mut trace)
+        }
+        Command::Reflect(_) | Command::Precompact(_) => {
+            reflect::run_precompact(&config, &input, out, &mut trace)
+        }
+        Command::Stop(_) => stop::run(&config, &input, out, &mut trace),
+        Command::Notify(_) => notify::run(&config, &input, out, &mut trace),
+        Command::PostToolUse => posttooluse::run(&config, &input, out, &mut trace),
+        Command::UserPromptSubmit => capture::run_prompt(&config, &input, out, &mut trace),
+        Command::Analyze => analyze::run(&config, &input, format),
+        Command::Doctor | Command::Replay(_) | Command::Hooks(_) | Command::Config(_) => {
+            unreachable!("handled above")
+        }
+    };
+
+    // Nothing was emitted — the notice goes out on its own.
+    if notice.as_ref().is_some_and(|n| !n.shown.get()) {
+        out.emit(&HookResponse::Allow);
+    }
+
+    // The trace is for humans unless JSON was asked for explicitly.
+    if cli.global.explain && trace.decision.is_some() {
+        if cli.global.format == Some(Format::Json) {
+            eprintln!("{}", serde_json::json!({ "explain": trace }));
+        } else {
+            eprint!("{}", trace.render_text());
+        }
+    }
+
+    code
+}
+
+/// Assemble hook input. A `notify` payload argument is the whole input;
+/// otherwise CLI flags take precedence: if any hook input flag is present,
+/// flags are the whole input; otherwise the JSON payload is read from stdin.
+/// An empty `cwd` falls back to the process working directory.
+fn hook_input(global: &GlobalArgs, command: &Command) -> Option<HookInput> {
+    let flags_given =
+        global.cwd.is_some() || global.transcript.is_some() || command.has_input_flags();
+
+    let mut input = if let Command::Notify(NotifyArgs {
+        payload: Some(payload),
+    }) = command
+    {
+        match serde_json::from_str::<HookInput>(payload) {
+            Ok(input) => input,
+            Err(e) => {
+                eprintln!("forge-reflect: failed to parse notify payload JSON: {e}");
+                return None;
+            }
+        }
+    } else if flags_given {
+        let (stop_hook_active, trigger) = match command {
+            Command::Insight(args) => (args.stop.stop_hook_active, None),
+            Command::Reflect(args) => (args.stop.stop_hook_active, args.trigger.clone()),
+            Command::Stop(args) => (args.stop_hook_active, None),
+            Command::Precompact(args) => (false, args.trigger.clone()),
+            Command::Surface
+            | Command::Notify(_)
+            | Command::PostToolUse
+            | Command::UserPromptSubmit
+            | Command::Analyze
+            | Command::Doctor
+            | Command::Replay(_)
+            | Command::Hooks(_)
+            | Command::Config(_) => (false, None),
+        };
+        HookInput {
+            stop_hook_active,
+            cwd: global.cwd.clone().unwrap_or_default(),
+            transcript_path: global.transcript.clone().unwrap_or_default(),
+            trigger,
+            ..HookInput::default()
+        }
+    } else {
+        match crate::read_hook_stdin() {
+            Some(input) => input,
+            // Surface is best-effort: a malformed payload still gets a digest.
+            None if matches!(command, Command::Surface) => HookInput::default(),
+            None => return None,
+        }
+    };
+
+    if input.cwd.is_empty() {
+        input.cwd = std::env::current_dir()
+            .map(|p| p.to_string_lossy().into_owned())
+            .unwrap_or_default();
+    }
+
+    Some(input)
+}
+
+/// A Stop gate's conclusion, before anything is printed.
+enum Outcome {
+    /// Trace decision text.
+    Allow(String),
+    Block {
+        reason: String,
+        /// One-line obligation, e.g. "2 uncaptured insight(s): a, b".
+        summary: String,
+    },
+}
+
+/// Shared Stop gate prologue: hook guard, profile, `scope` rules, then read
+/// and analyze the transcript once. Returns None (with the trace decided)
+/// when the stop is allowed before analysis.
+fn stop_analysis(
+    gate: &str,
+    config: &Config,
+    input: &HookInput,
+    trace: &mut Trace,
+) -> Option<TranscriptAnalysis> {
+    trace.step(
+        "input",
+        format!("stop_hook_active={}", input.stop_hook_active),
+    );
+    if input.stop_hook_active {
+        eprintln!("forge-reflect[{gate}]: stop_hook_active, deferring");
+        trace.decide("allow \u{2014} stop_hook_active, deferring");
+        return None;
+    }
+    if !enforced(gate, config, input, trace) {
+        return None;
+    }
+
+    let Ok(transcript) = std::fs::read_to_string(&input.transcript_path) else {
+        eprintln!(
+            "forge-reflect[{gate}]: transcript unreadable at '{}', skipping",
+            input.transcript_path
+        );
+        trace.decide(format!(
+            "allow \u{2014} transcript unreadable at '{}'",
+            input.transcript_path
+        ));
+        return None;
+    };
+
+    let analysis = transcript::analyze_transcript(&transcript, config);
+    trace.record_analysis(&analysis, config);
+    Some(analysis)
+}
+
+/// The profile enforces and the `scope` rules include the cwd. Otherwise
+/// the trace is decided and `gate` may not act.
+fn enforced(gate: &str, config: &Config, input: &HookInput, trace: &mut Trace) -> bool {
+    if !config.profile.enforces() {
+        eprintln!("forge-reflect[{gate}]: profile silent, skipping");
+        trace.decide("allow \u{2014} profile silent");
+        return false;
+    }
+
+    let home = std::env::var("HOME").unwrap_or_default();
+    let scope = crate::scope::evaluate(&config.scope, &input.cwd, &home);
+    trace.step(
+        "scope",
+        format!("cwd '{}' \u{2014} {}", input.cwd, scope.describe()),
+    );
+    if !scope.included {
+        eprintln!(
+            "forge-reflect[{gate}]: cwd '{}' out of scope, skipping",
+            input.cwd
+        );
+        trace.decide("allow \u{2014} out of scope");
+        return false;
+    }
+    true
+}
+
+/// When the user typed an override phrase, record the `bypassed`
+/// obligations to the audit log, decide the trace and return true — the
+/// caller then allows the stop instead of blocking.
+fn honour_override(
+    gate: &str,
+    config: &Config,
+    input: &HookInput,
+    analysis: &TranscriptAnalysis,
+    bypassed: Vec<String>,
+    trace: &mut Trace,
+) -> bool {
+    let Some(found) = &analysis.user_override else {
+        return false;
+    };
+    eprintln!(
+        "forge-reflect[{gate}]: user override '{}', allowing: {}",
+        found.phrase,
+        bypassed.join("; ")
+    );
+    let store = state_store(config);
+    let id = session_id(input);
+    let now = chrono::Utc::now().timestamp();
+    let logged = store
+        .update(&id, now, |state| {
+            state.logged_override.replace(found.message) == Some(found.message)
+        })
+        .unwrap_or(false);
+    if !logged {
+        let record = OverrideRecord {
+            session_id: id,
+            recorded_at: now,
+            cwd: input.cwd.clone(),
+            gate: gate.to_string(),
+            phrase: found.phrase.clone(),
+            reason: found.reason.clone(),
+            bypassed,
+        };
+        if let Err(e) = store.append_override(&record) {
+            eprintln!("forge-reflect[{gate}]: override log append failed: {e}");
+        }
+    }
+    trace.decide(if found.reason.is_empty() {
+        format!("allow \u{2014} user override '{}'", found.phrase)
+    } else {
+        format!(
+            "allow \u{2014} user override '{}': {}",
+            found.phrase, found.reason
+        )
+    });
+    true
+}
+
+/// Session variables for message templates (see
+/// [`crate::prompt::template::SESSION`]). Without an analysis only
+/// `{insights_dir}` is set.
+fn message_vars(config: &Config, cwd: &str, analysis: Option<&TranscriptAnalysis>) -> Vars {
+    let vars = Vars::default().with(
+        "insights_dir",
+        config.resolve_memory_dir(cwd, Kind::Insight, &[]).display(),
+    );
+    let Some(analysis) = analysis else {
+        return vars;
+    };
+    let uncaptured = gate::uncaptured_insights(analysis);
+    let topics = if uncaptured.is_empty() {
+        String::new()
+    } else {
+        uncaptured.topics_display()
+    };
+    vars.with("duration", analysis.session_duration_minutes)
+        .with("user_messages", analysis.user_messages)
+        .with("tool_turns", analysis.tool_using_turns)
+        .with("count", uncaptured.total())
+        .with("topics", topics)
+}
+
+/// Session key for per-session files: the payload's `session_id`, else
+/// `SESSION_ID` / `PPID` from the environment, else our own pid.
+fn session_id(input: &HookInput) -> String {
+    if !input.session_id.is_empty() {
+        return input.session_id.clone();
+    }
+    std::env::var("SESSION_ID")
+        .or_else(|_| std::env::var("PPID"))
+        .unwrap_or_else(|_| std::process::id().to_string())
+}
+
+/// Session state store configured by `state.*`.
+fn state_store(config: &Config) -> Store {
+    Store::new(
+        Store::resolve_dir(&config.state.dir),
+        config.state.retention_days,
+    )
+}
+
+/// Config problems for the user, once per session for each distinct set.
+/// Stderr is hidden by the hosts, so hook output has to carry them.
+fn config_notice(config: &Config, input: &HookInput) -> Option<Notice> {
+    if config.issues.is_empty() {
+        return None;
+    }
+    let issues: Vec<String> = config.issues.iter().map(ToString::to_string).collect();
+    let now = chrono::Utc::now().timestamp();
+    let fresh = state_store(config)
+        .update(&session_id(input), now, |state| {
+            if state.config_issues == issues {
+                return false;
+            }
+            state.config_issues.clone_from(&issues);
+            true
+        })
+        .unwrap_or(true);
+    fresh.then(|| Notice {
+        message: format!(
+            "forge-reflect config: {} problem(s) \u{2014} {}. Run `forge-reflect doctor`.",
+            issues.len(),
+            issues.join("; ")
+        ),
+        shown: Cell::new(false),
+    })
+}
+
+/// A message shown to the user with the first response a command emits.
+#[derive(Debug)]
+struct Notice {
+    message: String,
+    shown: Cell<bool>,
+}
+
+/// The `audit-only` profile's destination for hook output.
+#[derive(Debug)]
+struct Audit {
+    store: Store,
+    session_id: String,
+    cwd: String,
+    command: &'static str,
+}
+
+impl Audit {
+    fn record(&self, output: serde_json::Value) {
+        eprintln!(
+            "forge-reflect[{}]: audit-only, output withheld",
+            self.command
+        );
+        let record = AuditRecord {
+            session_id: self.session_id.clone(),
+            recorded_at: chrono::Utc::now().timestamp(),
+            cwd: self.cwd.clone(),
+            command: self.command.to_string(),
+            output,
+        };
+        if let Err(e) = self.store.append_audit(&record) {
+            eprintln!(
+                "forge-reflect[{}]: audit log append failed: {e}",
+                self.command
+            );
+        }
+    }
+}
+
+/// Where hook responses go: host protocol JSON or human text on stdout, or
+/// the audit log under `audit-only`.
+#[derive(Clone, Copy, Debug)]
+struct Output<'a> {
+    format: Format,
+    host: Host,
+    notice: Option<&'a Notice>,
+    audit: Option<&'a Audit>,
+}
+
+impl Output<'_> {
+    /// Responses reach the user rather than the audit log.
+    fn shown(self) -> bool {
+        self.audit.is_none()
+    }
+
+    fn emit(self, response: &HookResponse) {
+        let notice = self
+            .notice
+            .filter(|n| !n.shown.replace(true))
+            .map(|n| n.message.as_str());
+        let output = match self.format {
+            Format::Json => response.render_with_notice(self.host, notice),
+            Format::Text => response.render_text().map(serde_json::Value::String),
+        };
+        match (output, self.audit) {
+            (None, _) => {}
+            (Some(output), Some(audit)) => audit.record(output),
+            (Some(serde_json::Value::String(text)), None) => println!("{text}"),
+            (Some(json), None) => println!("{json}"),
+        }
+    }
+
+    fn block(self, reason: &str) {
+        self.emit(&HookResponse::Block {
+            reason: reason.to_string(),
+        });
+    }
+
+    fn context(self, event: &str, text: &str) {
+        self.emit(&HookResponse::InjectContext {
+            event: event.to_string(),
+            text: text.to_string(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,209 @@
+use super::stop::{self, Block};
+use super::{honour_override, session_id, state_store, stop_analysis, Output};
+use crate::config::Config;
+use crate::explain::Trace;
+use crate::response::HookResponse;
+use crate::state::PendingReflection;
+use crate::HookInput;
+use chrono::NaiveDate;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+/// Codex `notify` program, run after every agent turn. Codex cannot block,
+/// so the Stop gates run over the session's rollout and whatever would have
+/// blocked is stored as `pending_reflection` in the session state for the
+/// `/PendingReflection` skill. A turn with nothing outstanding clears it.
+pub(super) fn run(config: &Config, input: &HookInput, out: Output, trace: &mut Trace) -> ExitCode {
+    if !input.event_type.is_empty() && input.event_type != "agent-turn-complete" {
+        eprintln!(
+            "forge-reflect[notify]: ignoring '{}' notification",
+            input.event_type
+        );
+        return ExitCode::SUCCESS;
+    }
+
+    let Some(transcript_path) = rollout_path(config, input, trace) else {
+        return ExitCode::SUCCESS;
+    };
+    trace.step("rollout", transcript_path.clone());
+    // Older Codex payloads carry no thread-id; the rollout name embeds it.
+    let session = if input.session_id.is_empty() {
+        Path::new(&transcript_path)
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    } else {
+        input.session_id.clone()
+    };
+    let input = HookInput {
+        cwd: input.cwd.clone(),
+        transcript_path,
+        session_id: session,
+        ..HookInput::default()
+    };
+
+    let Some(analysis) = stop_analysis("notify", config, &input, trace) else {
+        return ExitCode::SUCCESS;
+    };
+    let blocks = stop::evaluate_gates(config, &input, &analysis, trace);
+    let obligations: Vec<String> = blocks.iter().map(Block::obligation).collect();
+
+    let pending = if blocks.is_empty() {
+        trace.decide("allow \u{2014} nothing pending");
+        None
+    } else if honour_override(
+        "notify",
+        config,
+        &input,
+        &analysis,
+        obligations.clone(),
+        trace,
+    ) {
+        None
+    } else {
+        Some(PendingReflection {
+            recorded_at: chrono::Utc::now().timestamp(),
+            cwd: input.cwd.clone(),
+            transcript: input.transcript_path.clone(),
+            obligations: obligations.clone(),
+            reason: stop::block_reason(&blocks),
+        })
+    };
+
+    let id = session_id(&input);
+    let now = chrono::Utc::now().timestamp();
+    let had_pending = pending.is_some();
+    if let Err(e) = state_store(config).update(&id, now, |state| {
+        state.pending_reflection = pending;
+        state.rollout = Some(input.transcript_path.clone());
+    }) {
+        eprintln!("forge-reflect[notify]: state update failed: {e}");
+        return ExitCode::SUCCESS;
+    }
+
+    if had_pending {
+        eprintln!(
+            "forge-reflect[notify]: reflection pending: {}",
+            obligations.join("; ")
+        );
+        out.emit(&HookResponse::Notify {
+            message: format!(
+                "Reflection pending \u{2014} run /PendingReflection: {}",
+                obligations.join("; ")
+            ),
+        });
+        trace.decide(format!(
+            "pending \u{2014} {} obligation(s) stored",
+            obligations.len()
+        ));
+    }
+    ExitCode::SUCCESS
+}
+
+/// The payload's transcript path, else the rollout cached for the thread,
+/// else the one [`find_rollout`] finds — cached for the thread's later turns.
+fn rollout_path(config: &Config, input: &HookInput, trace: &mut Trace) -> Option<String> {
+    if !input.transcript_path.is_empty() {
+        return Some(input.transcript_path.clone());
+    }
+    // A thread's rollout stays put, so the path found on its first turn is
+    // reused from the session state.
+    let store = state_store(config);
+    if !input.session_id.is_empty() {
+        let cached = store
+            .load(&input.session_id)
+            .rollout
+            .filter(|path| Path::new(path).is_file());
+        if cached.is_some() {
+            return cached;
+        }
+    }
+
+    let sessions = sessions_dir();
+    let today = chrono::Local::now().date_naive();
+    let Some(rollout) = find_rollout(&sessions, &input.session_id, today) else {
+        eprintln!(
+            "forge-reflect[notify]: no rollout under '{}', skipping",
+            sessions.display()
+        );
+        trace.decide("allow \u{2014} no Codex rollout found");
+        return None;
+    };
+    let rollout = rollout.to_string_lossy().into_owned();
+    // Cached now, so a turn skipped below does not repeat the search.
+    if !input.session_id.is_empty() {
+        let now = chrono::Utc::now().timestamp();
+        if let Err(e) = store.update(&input.session_id, now, |state| {
+            state.rollout = Some(rollout.clone());
+        }) {
+            eprintln!("forge-reflect[notify]: state update failed: {e}");
+        }
+    }
+    Some(rollout)
+}
+
+/// `$CODEX_HOME/sessions`, else `~/.codex/sessions`.
+fn sessions_dir() -> PathBuf {
+    let non_empty = |var| std::env::var(var).ok().filter(|v: &String| !v.is_empty());
+    non_empty("CODEX_HOME")
+        .map(PathBuf::from)
+        .or_else(|| non_empty("HOME").map(|home| Path::new(&home).join(".codex")))
+        .unwrap_or_default()
+        .join("sessions")
+}
+
+/// The `rollout-*.jsonl` whose name contains `thread_id`, or the most
+/// recently modified rollout when the payload has no thread id. Codex files
+/// rollouts under `YYYY/MM/DD/` by start date, so `today` and the day before
+/// are searched first; a thread started earlier is looked up in the whole
+/// `dir`.
+fn find_rollout(dir: &Path, thread_id: &str, today: NaiveDate) -> Option<PathBuf> {
+    let mut rollouts = Vec::new();
+    for day in [Some(today), today.pred_opt()].into_iter().flatten() {
+        collect_rollouts(
+            &dir.join(day.format("%Y/%m/%d").to_string()),
+            false,
+            &mut rollouts,
+        );
+    }
+    if thread_id.is_empty() {
+        return rollouts
+            .into_iter()
+            .max_by_key(|path| fs::metadata(path).and_then(|m| m.modified()).ok());
+    }
+    let names_thread = |path: &PathBuf| {
+        path.file_name()
+            .is_some_and(|name| name.to_string_lossy().contains(thread_id))
+    };
+    if let Some(rollout) = rollouts.into_iter().find(names_thread) {
+        return Some(rollout);
+    }
+    eprintln!(
+        "forge-reflect[notify]: no rollout for '{thread_id}' dated {today} or the day before, searching '{}'",
+        dir.display()
+    );
+    let mut rollouts = Vec::new();
+    collect_rollouts(dir, true, &mut rollouts);
+    rollouts.into_iter().find(names_thread)
+}
+
+/// `rollout-*.jsonl` files in `dir`, and in its subdirectories when
+/// `recursive`.
+fn collect_rollouts(dir: &Path, recursive: bool, rollouts: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.filter_map(Result::ok) {
+        let path = entry.path();
+        if recursive && path.is_dir() {
+            collect_rollouts(&path, true, rollouts);
+        } else if path.extension().is_some_and(|e| e == "jsonl")
+            && path
+                .file_name()
+                .is_some_and(|n| n.to_string_lossy().starts_with("rollout-"))
+        {
+            rollouts.push(path);
+        }
+    }
+}
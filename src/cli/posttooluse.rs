@@ -0,0 +1,91 @@
+use super::{insight, message_vars, session_id, state_store, surface, Output};
+use crate::advisory;
+use crate::config::{Config, SurfaceOn};
+use crate::explain::Trace;
+use crate::prompt::template;
+use crate::HookInput;
+use std::fs;
+use std::process::ExitCode;
+
+/// Phase 1: surface digest on the first trigger skill of the session, when
+/// `surface.on` includes `first_skill` and `SessionStart` has not shown it.
+/// Phase 2: count the tool call and run the advisory insight check when a
+/// trigger fires. At most one JSON object is emitted per invocation.
+pub(super) fn run(config: &Config, input: &HookInput, out: Output, trace: &mut Trace) -> ExitCode {
+    let session = session_id(input);
+    let store = state_store(config);
+    let now = chrono::Utc::now().timestamp();
+
+    let skill = input.skill();
+    let show_surface = config.surface.on.contains(&SurfaceOn::FirstSkill)
+        && advisory::is_surface_trigger(skill, config)
+        && store
+            .update(&session, now, |state| {
+                !std::mem::replace(&mut state.surface_shown, true)
+            })
+            .unwrap_or_else(|e| {
+                eprintln!("forge-reflect[posttooluse]: state update failed: {e}");
+                false
+            });
+    if show_surface {
+        if let Some(digest) = surface::digest(config, &input.cwd, out.shown()) {
+            let context = format!(
+                "{}\n\n{}",
+                template::render(
+                    &config.surface.post_tool_use_prompt,
+                    &message_vars(config, &input.cwd, None)
+                ),
+                digest.trim_end()
+            );
+            out.context("PostToolUse", &context);
+            trace.decide(format!("surface \u{2014} first '{skill}' this session"));
+            return ExitCode::SUCCESS;
+        }
+    }
+
+    if !config.profile.enforces() {
+        trace.decide("allow \u{2014} profile silent");
+        return ExitCode::SUCCESS;
+    }
+    let tick = store.update(&session, now, |state| {
+        let trigger = advisory::tick(&mut state.advisory, now, &config.advisory, || {
+            count_markers(&input.transcript_path, &config.insight_marker)
+        });
+        (trigger, state.advisory.clone())
+    });
+    let (trigger, counters) = match tick {
+        Ok(tick) => tick,
+        Err(e) => {
+            eprintln!("forge-reflect[posttooluse]: state update failed: {e}");
+            trace.decide("allow \u{2014} session state unavailable");
+            return ExitCode::SUCCESS;
+        }
+    };
+
+    trace.step(
+        "counters",
+        format!(
+            "{} tool call(s) since last interval, started at {}",
+            counters.tool_calls, counters.started_at
+        ),
+    );
+    let Some(trigger) = trigger else {
+        trace.decide("allow \u{2014} no advisory trigger");
+        return ExitCode::SUCCESS;
+    };
+    trace.step("trigger", trigger.label());
+
+    if input.transcript_path.is_empty() {
+        trace.decide("allow \u{2014} no transcript path");
+        return ExitCode::SUCCESS;
+    }
+    insight::run(config, input, out, true, trace)
+}
+
+/// Lines in the transcript containing `marker` — a cheap pre-check before
+/// the full analysis.
+fn count_markers(transcript_path: &str, marker: &str) -> usize {
+    fs::read_to_string(transcript_path).map_or(0, |content| {
+        content.lines().filter(|l| l.contains(marker)).count()
+    })
+}
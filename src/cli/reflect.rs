@@ -0,0 +1,190 @@
+use super::{
+    honour_override, message_vars, session_id, state_store, stop_analysis, Outcome, Output,
+};
+use crate::capture::Kind;
+use crate::config::Config;
+use crate::explain::Trace;
+use crate::gate;
+use crate::prompt::{self, template};
+use crate::transcript::{self, TranscriptAnalysis};
+use crate::HookInput;
+use std::fs;
+use std::process::ExitCode;
+
+pub(super) fn run(config: &Config, input: &HookInput, out: Output, trace: &mut Trace) -> ExitCode {
+    let Some(analysis) = stop_analysis("reflect", config, input, trace) else {
+        return ExitCode::SUCCESS;
+    };
+    match evaluate(config, input, &analysis, trace) {
+        Outcome::Allow(decision) => trace.decide(decision),
+        Outcome::Block { reason, summary } => {
+            let bypassed = vec![format!("reflect: {summary}")];
+            if !honour_override("reflect", config, input, &analysis, bypassed, trace) {
+                out.block(&reason);
+                trace.decide(format!("block \u{2014} {summary}"));
+            }
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+/// Soft heuristic: a substantial session (duration + floor) with no memory
+/// writes blocks with the reflection skill, or warns when the profile does
+/// not block.
+pub(super) fn evaluate(
+    config: &Config,
+    input: &HookInput,
+    analysis: &TranscriptAnalysis,
+    trace: &mut Trace,
+) -> Outcome {
+    trace.threshold(
+        "duration min",
+        analysis.session_duration_minutes,
+        u64::from(config.duration_threshold_minutes),
+    );
+    trace.threshold(
+        "user messages",
+        analysis.user_messages as u64,
+        config.user_msg_floor as u64,
+    );
+    if !gate::reflect_substantial(analysis, config) {
+        eprintln!(
+            "forge-reflect[reflect]: session not substantial ({} min, {} msgs), allowing",
+            analysis.session_duration_minutes, analysis.user_messages
+        );
+        return Outcome::Allow("allow \u{2014} session not substantial".to_string());
+    }
+
+    if analysis.has_memory_write {
+        return Outcome::Allow(format!(
+            "allow \u{2014} {} memory write(s) credited",
+            analysis.memory_writes.len()
+        ));
+    }
+
+    if !gate::reflect_blocks(analysis, config) {
+        eprintln!(
+            "forge-reflect[reflect]: warn \u{2014} substantial session ({} min, {} msgs) with no memory writes",
+            analysis.session_duration_minutes, analysis.user_messages
+        );
+        return Outcome::Allow(format!(
+            "warn \u{2014} no memory writes (profile {})",
+            config.profile.name()
+        ));
+    }
+
+    eprintln!(
+        "forge-reflect[reflect]: blocking \u{2014} substantial session ({} min, {} msgs) with no memory writes",
+        analysis.session_duration_minutes, analysis.user_messages
+    );
+    let skill_path = config.resolve_user_path(&input.cwd, &config.reflection);
+    let skill = prompt::load_pattern_abs(&skill_path);
+    trace.step(
+        "reason",
+        if skill.is_some() {
+            format!("skill {}", skill_path.display())
+        } else {
+            format!("fallback_reason ({} unreadable)", skill_path.display())
+        },
+    );
+    Outcome::Block {
+        reason: skill.unwrap_or_else(|| {
+            template::render(
+                &config.fallback_reason,
+                &message_vars(config, &input.cwd, Some(analysis)),
+            )
+        }),
+        summary: "substantial session with no memory writes".to_string(),
+    }
+}
+
+/// `PreCompact`: inject reflection prompt with reusability filter + uncaptured topics.
+/// Runs everywhere (no directory scope check).
+pub(super) fn run_precompact(
+    config: &Config,
+    input: &HookInput,
+    out: Output,
+    trace: &mut Trace,
+) -> ExitCode {
+    if !config.profile.enforces() {
+        trace.decide("allow \u{2014} profile silent");
+        return ExitCode::SUCCESS;
+    }
+    let cwd = input.cwd.as_str();
+    let analysis = (!input.transcript_path.is_empty())
+        .then(|| fs::read_to_string(&input.transcript_path).ok())
+        .flatten()
+        .map(|transcript| transcript::analyze_transcript(&transcript, config));
+    let mut vars = message_vars(config, cwd, analysis.as_ref());
+    if let Some(analysis) = &analysis {
+        trace.record_analysis(analysis, config);
+        let uncaptured = gate::unwritten_topics(analysis);
+        let capped: Vec<_> = uncaptured.iter().take(5).copied().collect();
+        vars = vars.with("topics", capped.join(", "));
+        if !uncaptured.is_empty() && config.precompact_agent {
+            if let Some(path) = save_candidates(&uncaptured, config, input, analysis) {
+                vars = vars.with("drafts", path);
+            }
+        }
+        eprintln!(
+            "forge-reflect[reflect]: precompact - {} msgs, {} tool turns, {} min, {} uncaptured",
+            analysis.user_messages,
+            analysis.tool_using_turns,
+            analysis.session_duration_minutes,
+            uncaptured.len()
+        );
+    }
+    let skill_path = config.resolve_user_path(cwd, &config.reflection);
+    let reason = prompt::load_pattern_abs(&skill_path)
+        .unwrap_or_else(|| template::render(&config.fallback_reason, &vars));
+
+    trace.step(
+        "trigger",
+        input
+            .trigger
+            .clone()
+            .unwrap_or_else(|| "manual".to_string()),
+    );
+    trace.decide("inject \u{2014} precompact reflection prompt");
+    let context = format!(
+        "{}{reason}{}",
+        template::render(&config.precompact_prefix, &vars),
+        template::render(&config.precompact_suffix, &vars)
+    );
+    out.context("PreCompact", &context);
+
+    ExitCode::SUCCESS
+}
+
+/// Store the candidates for `/MemoryCapture` in the session state document.
+/// Returns the document path.
+fn save_candidates(
+    uncaptured: &[&str],
+    config: &Config,
+    input: &HookInput,
+    analysis: &TranscriptAnalysis,
+) -> Option<String> {
+    let cwd = input.cwd.as_str();
+    let id = session_id(input);
+    let candidates = serde_json::json!({
+        "session_id": id,
+        "user_messages": analysis.user_messages,
+        "tool_turns": analysis.tool_using_turns,
+        "duration_minutes": analysis.session_duration_minutes,
+        "insights_dir": config.resolve_memory_dir(cwd, Kind::Insight, &[]).to_string_lossy(),
+        "imperatives_dir": config.resolve_memory_dir(cwd, Kind::Imperative, &[]).to_string_lossy(),
+        "ideas_dir": config.resolve_memory_dir(cwd, Kind::Idea, &[]).to_string_lossy(),
+        "topics": uncaptured,
+    });
+    let store = state_store(config);
+    let now = chrono::Utc::now().timestamp();
+    if let Err(e) = store.update(&id, now, |state| {
+        state.precompact_candidates = Some(candidates);
+    }) {
+        eprintln!("forge-reflect[reflect]: state update failed: {e}");
+        return None;
+    }
+    let path = store.path(&id).to_string_lossy().into_owned();
+    eprintln!("forge-reflect[reflect]: wrote candidates to {path}");
+    Some(path)
+}
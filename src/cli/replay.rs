@@ -0,0 +1,76 @@
+use super::{Format, ReplayArgs};
+use crate::config::Config;
+use crate::replay;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+/// Replay every `*.jsonl` under the given directories. Exits 1 if none are found.
+pub(super) fn run(config: &Config, args: &ReplayArgs, format: Format) -> ExitCode {
+    let candidate = args
+        .compare
+        .as_deref()
+        .map(|p| Config::load_with(Some(p), ""));
+
+    let mut reports = Vec::new();
+    for dir in &args.dirs {
+        let mut files = Vec::new();
+        collect_jsonl(dir, &mut files);
+        files.sort();
+        for file in files {
+            let Ok(content) = fs::read_to_string(&file) else {
+                eprintln!(
+                    "forge-reflect[replay]: unreadable '{}', skipping",
+                    file.display()
+                );
+                continue;
+            };
+            let session = file
+                .strip_prefix(dir)
+                .unwrap_or(&file)
+                .display()
+                .to_string();
+            reports.push(replay::replay_session(
+                &session,
+                &content,
+                config,
+                candidate.as_ref(),
+            ));
+        }
+    }
+
+    if reports.is_empty() {
+        eprintln!("forge-reflect[replay]: no .jsonl transcripts found");
+        return ExitCode::FAILURE;
+    }
+
+    let summary = replay::summarize(&reports, args.top);
+    match format {
+        Format::Json => {
+            let output = serde_json::json!({ "sessions": reports, "summary": summary });
+            println!("{output}");
+        }
+        Format::Text => print!("{}", replay::render_text(&reports, &summary)),
+    }
+
+    ExitCode::SUCCESS
+}
+
+/// Recursively collect `*.jsonl` files. A file argument is taken as-is.
+fn collect_jsonl(path: &Path, files: &mut Vec<PathBuf>) {
+    if path.is_file() {
+        files.push(path.to_path_buf());
+        return;
+    }
+    let Ok(entries) = fs::read_dir(path) else {
+        return;
+    };
+    for entry in entries.filter_map(Result::ok) {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_jsonl(&path, files);
+        } else if path.extension().is_some_and(|e| e == "jsonl") {
+            files.push(path);
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use super::{
+    capture, honour_override, insight, reflect, session_id, state_store, stop_analysis, Outcome,
+    Output,
+};
+use crate::config::{Config, StopGate};
+use crate::explain::Trace;
+use crate::state::LedgerEntry;
+use crate::transcript::TranscriptAnalysis;
+use crate::HookInput;
+use std::fmt::Write;
+use std::process::ExitCode;
+
+/// Analyze the transcript once and apply each gate in `stop_gates` order.
+/// Every blocking gate contributes its reason to a single block decision,
+/// so the agent sees all outstanding obligations at once.
+pub(super) fn run(config: &Config, input: &HookInput, out: Output, trace: &mut Trace) -> ExitCode {
+    let Some(analysis) = stop_analysis("stop", config, input, trace) else {
+        return ExitCode::SUCCESS;
+    };
+
+    let blocks = evaluate_gates(config, input, &analysis, trace);
+    if blocks.is_empty() {
+        trace.decide("allow \u{2014} no gate blocked");
+        return ExitCode::SUCCESS;
+    }
+    let obligations = blocks.iter().map(Block::obligation).collect();
+    if honour_override("stop", config, input, &analysis, obligations, trace) {
+        return ExitCode::SUCCESS;
+    }
+    // Audited blocks never reach the agent, so they do not spend the budget.
+    if !config.profile.audits() && !within_budget(config, input, &blocks, trace) {
+        return ExitCode::SUCCESS;
+    }
+
+    let names: Vec<_> = blocks.iter().map(|b| b.gate.name()).collect();
+    out.block(&block_reason(&blocks));
+    trace.decide(format!("block \u{2014} {}", names.join(" + ")));
+    ExitCode::SUCCESS
+}
+
+/// Apply each gate in `stop_gates` order; one [`Block`] per blocking gate.
+pub(super) fn evaluate_gates(
+    config: &Config,
+    input: &HookInput,
+    analysis: &TranscriptAnalysis,
+    trace: &mut Trace,
+) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    for &gate in &config.stop_gates {
+        let outcome = match gate {
+            StopGate::Insight => insight::evaluate(config, input, analysis, trace),
+            StopGate::Reflect => reflect::evaluate(config, input, analysis, trace),
+            StopGate::Capture => capture::evaluate(config, input, analysis, trace),
+        };
+        match outcome {
+            Outcome::Allow(decision) => trace.step(gate.name(), decision),
+            Outcome::Block { reason, summary } => {
+                trace.step(gate.name(), format!("block \u{2014} {summary}"));
+                blocks.push(Block {
+                    gate,
+                    reason,
+                    summary,
+                });
+            }
+        }
+    }
+    blocks
+}
+
+pub(super) struct Block {
+    gate: StopGate,
+    reason: String,
+    summary: String,
+}
+
+impl Block {
+    pub(super) fn obligation(&self) -> String {
+        format!("{}: {}", self.gate.name(), self.summary)
+    }
+}
+
+/// Count this block against `block_budget`. Once the budget is spent, record
+/// the obligations to the ledger (first time only), warn and return false so
+/// the stop is allowed. State errors fail open to blocking — the budget is
+/// loop protection, not enforcement.
+fn within_budget(config: &Config, input: &HookInput, blocks: &[Block], trace: &mut Trace) -> bool {
+    if config.block_budget == 0 {
+        return true;
+    }
+    let store = state_store(config);
+    let session = session_id(input);
+    let now = chrono::Utc::now().timestamp();
+    let budget = config.block_budget;
+
+    let spent = store.update(&session, now, |state| {
+        if state.stop_blocks < budget {
+            state.stop_blocks += 1;
+            return (false, state.stop_blocks, false);
+        }
+        let first = !std::mem::replace(&mut state.budget_exhausted, true);
+        (true, state.stop_blocks, first)
+    });
+    let (exhausted, used, record) = match spent {
+        Ok(spent) => spent,
+        Err(e) => {
+            eprintln!("forge-reflect[stop]: state update failed: {e}");
+            return true;
+        }
+    };
+    trace.step("budget", format!("{used}/{budget} block(s) used"));
+    if !exhausted {
+        return true;
+    }
+
+    let obligations: Vec<String> = blocks.iter().map(Block::obligation).collect();
+    eprintln!(
+        "forge-reflect[stop]: warn \u{2014} block budget exhausted ({budget}), allowing: {}",
+        obligations.join("; ")
+    );
+    if record {
+        let entry = LedgerEntry {
+            session_id: session,
+            recorded_at: now,
+            cwd: input.cwd.clone(),
+            obligations,
+        };
+        if let Err(e) = store.append_ledger(&entry) {
+            eprintln!("forge-reflect[stop]: ledger append failed: {e}");
+        }
+    }
+    trace.decide(format!(
+        "warn \u{2014} block budget exhausted ({budget}), obligations in ledger"
+    ));
+    false
+}
+
+/// The single gate's reason, or a numbered list of every blocking gate's.
+pub(super) fn block_reason(blocks: &[Block]) -> String {
+    if let [block] = blocks {
+        return block.reason.clone();
+    }
+    let mut merged = format!("{} obligations before ending:", blocks.len());
+    for (i, block) in blocks.iter().enumerate() {
+        let _ = write!(merged, "\n\n{}. {}", i + 1, block.reason.trim());
+    }
+    merged
+}
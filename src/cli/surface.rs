@@ -0,0 +1,223 @@
+use super::{message_vars, session_id, state_store, Format, Output};
+use crate::config::{Config, SurfaceOn};
+use crate::prompt::template;
+use crate::surface;
+use crate::HookInput;
+use chrono::{DateTime, Datelike, Local, NaiveDate};
+use std::fs;
+use std::process::ExitCode;
+
+/// `SessionStart` payloads (they carry a `source`) show the digest when
+/// `surface.on` lists the moment and, for startup, only once per session.
+/// Without a payload — `/Surface`, the opencode adapter — it always shows.
+pub(super) fn run(config: &Config, input: &HookInput, out: Output) -> ExitCode {
+    let hook = input.hook_event_name == "SessionStart" || !input.source.is_empty();
+    if hook && !should_show(config, input) {
+        return ExitCode::SUCCESS;
+    }
+    // Text always goes to stdout, even under `audit-only`.
+    let shown = matches!(out.format, Format::Text) || out.shown();
+    let Some(digest) = digest(config, &input.cwd, shown) else {
+        return ExitCode::SUCCESS;
+    };
+
+    match out.format {
+        Format::Text => print!("{digest}"),
+        Format::Json if hook => out.context(
+            "SessionStart",
+            &format!(
+                "{}\n\n{}",
+                template::render(
+                    &config.surface.session_start_prompt,
+                    &message_vars(config, &input.cwd, None)
+                ),
+                digest.trim_end()
+            ),
+        ),
+        Format::Json => out.context("SessionStart", &digest),
+    }
+
+    ExitCode::SUCCESS
+}
+
+/// Check `surface.on` and claim the session's showing. Compaction drops the
+/// earlier digest from context, so it shows again and claims the one-shot
+/// for first-skill too.
+fn should_show(config: &Config, input: &HookInput) -> bool {
+    let moment = SurfaceOn::from_source(&input.source);
+    if !config.surface.on.contains(&moment) {
+        eprintln!(
+            "forge-reflect[surface]: source '{}' not in surface.on, skipping",
+            input.source
+        );
+        return false;
+    }
+    let now = chrono::Utc::now().timestamp();
+    state_store(config)
+        .update(&session_id(input), now, |state| {
+            let shown = std::mem::replace(&mut state.surface_shown, true);
+            moment == SurfaceOn::Compact || !shown
+        })
+        .unwrap_or_else(|e| {
+            eprintln!("forge-reflect[surface]: state update failed: {e}");
+            true
+        })
+}
+
+/// Build the surface digest for `cwd`. Returns None when every section is
+/// empty. `shown`: the digest reaches the user, so the ledger is consumed.
+pub(super) fn digest(config: &Config, cwd: &str, shown: bool) -> Option<String> {
+    let today = Local::now().date_naive();
+    let day_of_year = today.ordinal();
+
+    let mut sections: Vec<String> = Vec::new();
+
+    // --- Obligations left by sessions that ran out of block budget ---
+    if let Some(s) = ledger_section(config, shown) {
+        sections.push(s);
+    }
+
+    // Inspiration pools only — task coverage (overdue, due-soon, yesterday)
+    // lives in /DailyPlan. Surface is for serendipity and rediscovery.
+
+    // --- Stale ideas ---
+    if let Some(s) = ideas_section(config, cwd, today, day_of_year) {
+        sections.push(s);
+    }
+
+    // --- Rediscovery pool (tabs + backlog, mixed) ---
+    if let Some(s) = rediscovery_section(config, cwd, day_of_year) {
+        sections.push(s);
+    }
+
+    if sections.is_empty() {
+        return None;
+    }
+
+    let mut digest = format!("\u{1f4cc} Surface {}\n", "\u{2500}".repeat(45));
+    for s in &sections {
+        digest.push_str(s);
+    }
+    digest.push_str(&"\u{2500}".repeat(49));
+    digest.push('\n');
+    Some(digest)
+}
+
+/// Ledger section. A shown digest consumes the ledger, so each entry reaches
+/// the user once; an audited one leaves it for the next.
+fn ledger_section(config: &Config, shown: bool) -> Option<String> {
+    let store = state_store(config);
+    let entries = if shown {
+        store.take_ledger()
+    } else {
+        store.ledger()
+    };
+    let entries = entries
+        .map_err(|e| eprintln!("forge-reflect[surface]: ledger unreadable: {e}"))
+        .ok()?;
+    let dated: Vec<_> = entries
+        .into_iter()
+        .map(|entry| {
+            let date = DateTime::from_timestamp(entry.recorded_at, 0).map_or_else(
+                || Local::now().date_naive(),
+                |t| t.with_timezone(&Local).date_naive(),
+            );
+            (date, entry.obligations)
+        })
+        .collect();
+    surface::format_ledger(&dated)
+}
+
+/// Resolve a user-content path (relative to vault root) in every vault.
+fn resolve_user(config: &Config, cwd: &str, relative: &str) -> Vec<std::path::PathBuf> {
+    config.resolve_user_paths(cwd, relative)
+}
+
+/// Ideas section: stale open ideas, across vaults.
+fn ideas_section(config: &Config, cwd: &str, today: NaiveDate, day_of_year: u32) -> Option<String> {
+    let cutoff = today - chrono::Duration::days(i64::from(config.surface.ideas_cutoff_days));
+    let mut entries = Vec::new();
+
+    let read_dirs = resolve_user(config, cwd, &config.memory.ideas)
+        .into_iter()
+        .filter_map(|ideas_dir| fs::read_dir(ideas_dir).ok());
+    for entry in read_dirs.flatten() {
+        let Ok(entry) = entry else { continue };
+        let path = entry.path();
+        if path.extension().is_none_or(|e| e != "md") {
+            continue;
+        }
+
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let title = extract_frontmatter_value(&content, "title").unwrap_or_default();
+        let status = extract_frontmatter_value(&content, "status").unwrap_or_default();
+        let created = extract_frontmatter_value(&content, "created").unwrap_or_default();
+
+        if !title.is_empty() {
+            entries.push((title, status, created));
+        }
+    }
+
+    surface::parse_ideas(&entries, cutoff, day_of_year, config.surface.max_items)
+}
+
+/// Rediscovery section: rotate through a mixed pool of tabs + backlog items.
+fn rediscovery_section(config: &Config, cwd: &str, day_of_year: u32) -> Option<String> {
+    let mut pool: Vec<String> = Vec::new();
+
+    // Collect tab titles from each vault's most recent archive
+    for archive_dir in resolve_user(config, cwd, &config.surface.archive_dir) {
+        let mut candidates: Vec<_> = fs::read_dir(&archive_dir)
+            .ok()
+            .into_iter()
+            .flatten()
+            .filter_map(std::result::Result::ok)
+            .filter(|e| {
+                e.file_name()
+                    .to_string_lossy()
+                    .starts_with(&config.surface.archive_prefix)
+            })
+            .collect();
+
+        candidates.sort_by_key(|e| e.file_name().to_string_lossy().into_owned());
+        if let Some(latest) = candidates.last() {
+            if let Ok(content) = fs::read_to_string(latest.path()) {
+                pool.extend(surface::extract_tab_titles(&content));
+            }
+        }
+    }
+
+    // Collect open backlog items
+    for backlog_path in resolve_user(config, cwd, &config.backlog) {
+        if let Ok(content) = fs::read_to_string(&backlog_path) {
+            pool.extend(surface::extract_backlog_titles(&content));
+        }
+    }
+
+    surface::format_rotating_pool(&pool, config.surface.max_items, day_of_year as usize)
+}
+
+/// Extract a simple YAML frontmatter value (single-line, between --- fences).
+fn extract_frontmatter_value(content: &str, key: &str) -> Option<String> {
+    let mut in_frontmatter = false;
+    let prefix = format!("{key}:");
+
+    for line in content.lines() {
+        if line.trim() == "---" {
+            if in_frontmatter {
+                return None; // Passed end of frontmatter
+            }
+            in_frontmatter = true;
+            continue;
+        }
+        if in_frontmatter && line.starts_with(&prefix) {
+            let val = line[prefix.len()..].trim().to_string();
+            // Strip quotes
+            let val = val.trim_matches('"').trim_matches('\'').to_string();
+            return Some(val);
+        }
+    }
+    None
+}
@@ -0,0 +1,89 @@
+use super::*;
+
+fn parse(args: &[&str]) -> Cli {
+    Cli::try_parse_from(std::iter::once("forge-reflect").chain(args.iter().copied())).unwrap()
+}
+
+#[test]
+fn test_legacy_transcript_path_alias() {
+    let cli = parse(&[
+        "insight",
+        "--cwd",
+        "/tmp",
+        "--transcript-path",
+        "/tmp/t.jsonl",
+    ]);
+    assert_eq!(cli.global.transcript.as_deref(), Some("/tmp/t.jsonl"));
+    assert_eq!(cli.global.cwd.as_deref(), Some("/tmp"));
+}
+
+#[test]
+fn test_global_flags_before_subcommand() {
+    let cli = parse(&["--format", "text", "--config", "/tmp/c.yaml", "reflect"]);
+    assert_eq!(cli.global.format, Some(Format::Text));
+    assert_eq!(
+        cli.global.config.as_deref(),
+        Some(std::path::Path::new("/tmp/c.yaml"))
+    );
+    assert!(matches!(cli.command, Command::Reflect(_)));
+}
+
+#[test]
+fn test_default_formats() {
+    assert_eq!(parse(&["surface"]).command.default_format(), Format::Text);
+    assert_eq!(parse(&["analyze"]).command.default_format(), Format::Text);
+    assert_eq!(parse(&["insight"]).command.default_format(), Format::Json);
+    assert_eq!(
+        parse(&["precompact"]).command.default_format(),
+        Format::Json
+    );
+}
+
+#[test]
+fn test_flags_replace_stdin_payload() {
+    let cli = parse(&[
+        "reflect",
+        "--cwd",
+        "/work",
+        "--transcript",
+        "/tmp/t.jsonl",
+        "--stop-hook-active",
+        "--trigger",
+        "auto",
+    ]);
+    let input = hook_input(&cli.global, &cli.command).unwrap();
+    assert_eq!(input.cwd, "/work");
+    assert_eq!(input.transcript_path, "/tmp/t.jsonl");
+    assert!(input.stop_hook_active);
+    assert_eq!(input.trigger.as_deref(), Some("auto"));
+}
+
+#[test]
+fn test_insight_advisory_flag() {
+    let cli = parse(&["insight", "--advisory"]);
+    let Command::Insight(args) = cli.command else {
+        panic!("expected insight");
+    };
+    assert!(args.advisory);
+    assert!(!args.stop.stop_hook_active);
+}
+
+#[test]
+fn test_unknown_format_rejected() {
+    let result = Cli::try_parse_from(["forge-reflect", "--format", "yaml", "surface"]);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_posttooluse_subcommand_name() {
+    let cli = parse(&["posttooluse"]);
+    assert!(matches!(cli.command, Command::PostToolUse));
+    assert_eq!(cli.command.default_format(), Format::Json);
+}
+
+#[test]
+fn test_stop_hook_active_flag_is_input() {
+    let cli = parse(&["stop", "--stop-hook-active"]);
+    assert!(cli.command.has_input_flags());
+    assert_eq!(cli.command.default_format(), Format::Json);
+}
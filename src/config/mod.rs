@@ -1,15 +1,28 @@
-use serde::Deserialize;
+pub mod validate;
+
+pub use validate::Issue;
+
+use crate::capture::Kind;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
 use std::path::{Path, PathBuf};
 
 /// All configurable values for forge-reflect. Loaded from `config.yaml`
 /// (or `defaults.yaml`) in the plugin root directory. Falls back to compiled
 /// defaults if the file is missing or unreadable.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 #[serde(default)]
+#[schemars(deny_unknown_fields)]
 pub struct Config {
     // Transcript analysis
+    /// Marker that opens an insight block in assistant text.
     pub insight_marker: String,
+    /// Marker for an insight deliberately left uncaptured.
     pub insight_skip_marker: String,
+    /// Marker for an insight already written to memory.
     pub insight_captured_marker: String,
     /// Path fragments for substring matching in transcript `tool_use` entries.
     /// First element is the insights path (used for insight counting).
@@ -18,27 +31,55 @@ pub struct Config {
     pub write_tool_names: Vec<String>,
 
     // Substantiality thresholds
+    /// Insight gate: a session with this many tool-using turns...
     pub tool_turn_threshold: usize,
+    /// ...and this many user messages is substantial.
     pub user_msg_threshold: usize,
+    /// Reflect gate: a session this many minutes long...
     pub duration_threshold_minutes: u32,
+    /// ...with this many user messages is substantial.
     pub user_msg_floor: usize,
 
     // Skill file paths (user-root-relative)
+    /// Reflection skill, named in the reflect block.
     pub reflection: String,
+    /// Insight check skill, named in the insight block.
     pub insight_check: String,
 
-    // Data directory suffix for scope check
-    pub data_dir_suffix: String,
+    /// Where the Stop gates apply: ordered include/exclude globs over the
+    /// session's cwd, first match wins. No match = out of scope.
+    pub scope: Vec<ScopeRule>,
 
-    // Enforcement behaviour — from defaults.yaml, callers use unwrap_or(true).
-    pub insight_blocking: Option<bool>,
-    pub reflect_blocking: Option<bool>,
-    pub precompact_agent: Option<bool>,
+    /// Enforcement profile. A `scope` rule's profile overrides it for the
+    /// cwds it includes, and `FORGE_REFLECT_PROFILE` overrides both for one
+    /// session. After loading, the profile in effect.
+    pub profile: Profile,
+    /// `PreCompact` also stores the uncaptured topics as `/MemoryCapture`
+    /// candidates in the session state and asks for that skill.
+    pub precompact_agent: bool,
+    /// Gates the `stop` command applies, in order. A gate left out is skipped.
+    pub stop_gates: Vec<StopGate>,
+    /// User message phrases (case-insensitive) that let the Stop gates allow
+    /// exit without reflecting. Each use is recorded to `overrides.jsonl`.
+    pub override_phrases: Vec<String>,
+    /// Blocks `stop` may emit per session before it downgrades to a warning,
+    /// records the obligations to the ledger and allows exit. 0 = unlimited.
+    pub block_budget: u32,
 
-    // Hook message strings
+    // Hook message strings — templates (see `prompt::template`). Every one
+    // takes `{duration}`, `{user_messages}`, `{tool_turns}`, `{count}`
+    // (uncaptured insights), `{topics}` and `{insights_dir}`.
+    /// Reflect block reason when the reflection skill is unreadable.
     pub fallback_reason: String,
+    /// Text prepended to the `PreCompact` reflection prompt.
     pub precompact_prefix: String,
+    /// Text appended to the `PreCompact` reflection prompt. `{topics}` are
+    /// the unwritten topics (at most 5); `{drafts}` is the state document
+    /// holding `/MemoryCapture` candidates, with `precompact_agent` on.
+    pub precompact_suffix: String,
+    /// Insight block reason.
     pub uncaptured_insight_reason: String,
+    /// Advisory insight reminder (`PostToolUse`).
     pub insight_advisory_prompt: String,
 
     // Nested groups
@@ -47,50 +88,362 @@ pub struct Config {
     pub commands: CommandsConfig,
 
     // Flat
+    /// Backlog file (user-root-relative).
     pub backlog: String,
 
+    // Memory promotion
+    pub promote: PromoteConfig,
+
+    /// Skill allowlists by provider (`claude`, `gemini`, `codex`), read by
+    /// `install-skills`. Empty unless a YAML layer provides them.
+    pub skills: BTreeMap<String, SkillAllowlist>,
+
     // Surfacing
     pub surface: SurfaceConfig,
 
+    // PostToolUse advisory triggers
+    pub advisory: AdvisoryConfig,
+
+    // Per-session state store
+    pub state: StateConfig,
+
+    // UserPromptSubmit "remember this" requests
+    pub capture: CaptureConfig,
+
     // User content root (deserialized from YAML)
     pub user: UserConfig,
+
+    /// Module root the config was loaded from and the source that found it
+    /// (see [`Config::module_root_with_source`]).
+    #[serde(skip)]
+    pub module_root: Option<(PathBuf, &'static str)>,
+
+    /// Config files merged into this config, in merge order. Empty when
+    /// compiled defaults are in use.
+    #[serde(skip)]
+    pub layers: Vec<PathBuf>,
+
+    /// Environment variables applied on top of `layers` (see [`env_layer`]).
+    #[serde(skip)]
+    pub env_overrides: Vec<String>,
+
+    /// Where `profile` came from: `config`, `scope rule N` or
+    /// `$FORGE_REFLECT_PROFILE`.
+    #[serde(skip)]
+    pub profile_source: String,
+
+    /// Problems found while loading. The values they name were dropped or
+    /// reset to defaults; everything else applied.
+    #[serde(skip)]
+    pub issues: Vec<ConfigIssue>,
+}
+
+/// An [`Issue`] and the layer it came from: a file path, `environment`, or
+/// `merged config` for range checks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigIssue {
+    pub source: String,
+    pub issue: Issue,
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.source, self.issue)
+    }
+}
+
+/// A Stop gate, as named in `stop_gates`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum StopGate {
+    /// Hard rule: every ★ Insight block has a file.
+    Insight,
+    /// Soft heuristic: substantial sessions write memory.
+    Reflect,
+    /// Every "remember this" request has a file in its memory directory.
+    Capture,
+}
+
+impl SurfaceOn {
+    /// Moment for a `SessionStart` payload's `source`: `compact` after
+    /// compaction, startup for everything else (`startup`, `resume`, `clear`).
+    pub fn from_source(source: &str) -> Self {
+        if source == "compact" {
+            Self::Compact
+        } else {
+            Self::Startup
+        }
+    }
+}
+
+impl StopGate {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Insight => "insight",
+            Self::Reflect => "reflect",
+            Self::Capture => "capture",
+        }
+    }
 }
 
-#[derive(Debug, Default, Deserialize)]
+/// One `scope` rule. Exactly one of `include` / `exclude` is set; the glob
+/// matches the cwd (`~` = `$HOME`, `*` within a path segment, `**` across
+/// segments — `dir/**` also matches `dir` itself).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
 #[serde(default)]
+#[schemars(deny_unknown_fields)]
+pub struct ScopeRule {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exclude: Option<String>,
+    /// Enforcement for cwds this rule includes. Unset = the config as loaded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<Profile>,
+}
+
+/// How hard the hooks enforce, as named in `profile` and `scope` rules.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum Profile {
+    /// Stop gates block.
+    #[default]
+    Strict,
+    /// Stop gates only warn.
+    Advisory,
+    /// No Stop gates, advisory nudges or `PreCompact` prompt.
+    Silent,
+    /// Everything runs as under `strict`, but hook output is appended to
+    /// `audit.jsonl` in the state directory instead of printed.
+    AuditOnly,
+}
+
+impl Profile {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Strict => "strict",
+            Self::Advisory => "advisory",
+            Self::Silent => "silent",
+            Self::AuditOnly => "audit-only",
+        }
+    }
+
+    /// Stop gates, advisory nudges and the `PreCompact` prompt run.
+    pub fn enforces(self) -> bool {
+        self != Self::Silent
+    }
+
+    /// Stop gates block rather than warn.
+    pub fn blocks(self) -> bool {
+        matches!(self, Self::Strict | Self::AuditOnly)
+    }
+
+    /// Hook output is logged instead of printed.
+    pub fn audits(self) -> bool {
+        self == Self::AuditOnly
+    }
+}
+
+/// A moment the surface digest may be shown, as named in `surface.on`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SurfaceOn {
+    /// `SessionStart` with source `startup`, `resume` or `clear`.
+    Startup,
+    /// First skill in `surface.trigger_skills` (`PostToolUse`).
+    FirstSkill,
+    /// `SessionStart` with source `compact` — the digest was compacted away.
+    Compact,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, JsonSchema)]
+#[serde(default)]
+#[schemars(deny_unknown_fields)]
 pub struct UserConfig {
+    /// User content root; relative paths resolve against `$HOME`. Empty =
+    /// user paths resolve against the session's cwd.
     pub root: String,
+    /// Further named vaults. User paths go to the first one whose routing
+    /// matches, else to `root`; memory writes to any vault count and the
+    /// surface digest reads from all of them.
+    pub roots: Vec<UserRoot>,
 }
 
-#[derive(Debug, Deserialize)]
+/// A named vault next to `user.root`. Each routing list it sets must match
+/// for content to go here; one that sets none is only read from.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
 #[serde(default)]
+#[schemars(deny_unknown_fields)]
+pub struct UserRoot {
+    /// Name shown by `doctor`.
+    pub name: String,
+    /// Vault directory; relative paths resolve against `$HOME`.
+    pub path: String,
+    /// Session cwd globs, as in `scope`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub cwd: Vec<String>,
+    /// Memory types filed here. A rule with types routes memory files only,
+    /// not skills, journal or backlog.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub types: Vec<Kind>,
+    /// `#tags` in a capture request that file it here.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl UserRoot {
+    /// Content of `kind` (None = any user path) tagged `tags` from a session
+    /// in `cwd` goes to this vault.
+    fn routes(&self, cwd: &str, kind: Option<Kind>, tags: &[&str], home: &str) -> bool {
+        let routed = !self.cwd.is_empty() || !self.types.is_empty() || !self.tags.is_empty();
+        routed
+            && (self.cwd.is_empty()
+                || self
+                    .cwd
+                    .iter()
+                    .any(|glob| crate::scope::matches(glob, cwd.trim_end_matches('/'), home)))
+            && (self.types.is_empty() || kind.is_some_and(|kind| self.types.contains(&kind)))
+            && (self.tags.is_empty() || tags.iter().any(|tag| self.tags.iter().any(|t| t == tag)))
+    }
+}
+
+/// Memory directories (user-root-relative).
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(default)]
+#[schemars(deny_unknown_fields)]
 pub struct MemoryConfig {
     pub imperatives: String,
     pub insights: String,
     pub ideas: String,
 }
 
-#[derive(Debug, Deserialize)]
+/// Journal files (user-root-relative).
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 #[serde(default)]
+#[schemars(deny_unknown_fields)]
 pub struct JournalConfig {
+    /// Daily journal directory.
     pub daily: String,
 }
 
-#[derive(Debug, Deserialize)]
+/// Helper commands named in skill and hook text (module-relative).
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 #[serde(default)]
+#[schemars(deny_unknown_fields)]
 pub struct CommandsConfig {
     pub safe_read: String,
+    pub safe_write: String,
+}
+
+/// Landing zones for `/MemoryPromote` (user-root-relative).
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(default)]
+#[schemars(deny_unknown_fields)]
+pub struct PromoteConfig {
+    pub steering: String,
+    pub skills: String,
+    pub agents: String,
+    pub invariants: String,
+    pub scripts: String,
+    /// Promoted memory files are moved under `<archive>/<type>/`.
+    pub archive: String,
+}
+
+/// Per-skill install options in a `skills` allowlist.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(default)]
+#[schemars(deny_unknown_fields)]
+pub struct SkillOptions {
+    /// Template installed next to the skill (module-relative).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+/// Skills installed for one provider, by name. A bare `Name:` entry has no
+/// options.
+pub type SkillAllowlist = BTreeMap<String, Option<SkillOptions>>;
+
+/// The surface digest: stale ideas, reminders and rediscovered tabs and
+/// backlog items.
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 #[serde(default)]
+#[schemars(deny_unknown_fields)]
 pub struct SurfaceConfig {
+    /// Directory of tab snapshots for rediscovery (user-root-relative).
     pub archive_dir: String,
+    /// Filename prefix of those snapshots; the newest one is read.
     pub archive_prefix: String,
+    /// Reminders list the digest reads.
     pub reminders_list: String,
+    /// Open ideas older than this many days are surfaced as stale.
     pub ideas_cutoff_days: u32,
+    /// Reminders due within this many days count as due soon.
     pub due_soon_days: u32,
+    /// Items shown per digest section.
     pub max_items: usize,
+    /// When the digest fires. Startup and first skill share one showing per
+    /// session; compaction shows it again.
+    pub on: Vec<SurfaceOn>,
+    /// Skills whose first invocation in a session shows the digest.
+    pub trigger_skills: Vec<String>,
+    /// Instruction prepended to the digest in `SessionStart` context.
+    pub session_start_prompt: String,
+    /// Instruction prepended to the digest in `PostToolUse` context.
+    pub post_tool_use_prompt: String,
+}
+
+/// When `posttooluse` runs the advisory insight check.
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(default)]
+#[schemars(deny_unknown_fields)]
+pub struct AdvisoryConfig {
+    /// Check every N tool calls.
+    pub interval: u32,
+    /// Check once when the session is this old.
+    pub duration_minutes: u32,
+    /// Check when the transcript holds at least this many insight markers...
+    pub insight_threshold: usize,
+    /// ...counted every N tool calls.
+    pub insight_check_every: u32,
+    /// An unchanged uncaptured set is advised again only after this long.
+    pub cooldown_minutes: u32,
+    /// Escalation ladder: each advisory for outstanding insights uses the
+    /// next level, the last one repeating. Templates, as
+    /// `insight_advisory_prompt`. Empty = `insight_advisory_prompt`. The
+    /// Stop-time insight block is the rung after the last.
+    pub levels: Vec<String>,
+}
+
+/// Capture requests in user prompts ("remember that ...") and how they are
+/// classified. Cues match whole words, case-insensitively.
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(default)]
+#[schemars(deny_unknown_fields)]
+pub struct CaptureConfig {
+    /// Phrases that turn a prompt into a capture request.
+    pub phrases: Vec<String>,
+    /// Cues in the request text that make it an imperative...
+    pub imperative_cues: Vec<String>,
+    /// ...or an idea. Anything else is an insight.
+    pub idea_cues: Vec<String>,
+    /// `UserPromptSubmit` context. Template with `{kind}`, `{text}` and
+    /// `{dir}` besides the session variables.
+    pub prompt: String,
+    /// Stop block reason. Template where `{count}` is the unfulfilled
+    /// requests and `{requests}` lists them.
+    pub reason: String,
+}
+
+/// Where per-session state lives and how long it is kept.
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(default)]
+#[schemars(deny_unknown_fields)]
+pub struct StateConfig {
+    /// Absolute path. Empty = `$XDG_STATE_HOME/forge-reflect`.
+    pub dir: String,
+    /// Sessions untouched for longer are deleted, and older ledger, override
+    /// and audit log entries dropped. 0 keeps them forever.
+    pub retention_days: u32,
 }
 
 impl Default for Config {
@@ -109,17 +462,25 @@ impl Default for Config {
                 "edit".to_string(),
                 "write".to_string(),
                 "safe-write".to_string(),
+                "write_file".to_string(),
+                "replace".to_string(),
+                "apply_patch".to_string(),
             ],
             tool_turn_threshold: 10,
             user_msg_threshold: 4,
             duration_threshold_minutes: 15,
             user_msg_floor: 2,
-            insight_blocking: Some(true),
-            reflect_blocking: Some(true),
-            precompact_agent: Some(false),
+            profile: Profile::Strict,
+            precompact_agent: false,
+            stop_gates: vec![StopGate::Insight, StopGate::Reflect, StopGate::Capture],
+            block_budget: 3,
+            override_phrases: vec!["/NoReflect".to_string()],
             reflection: "Orchestration/Skills/SessionReflect/SKILL.md".to_string(),
             insight_check: "Orchestration/Skills/InsightCheck/SKILL.md".to_string(),
-            data_dir_suffix: "Data".to_string(),
+            scope: vec![ScopeRule {
+                include: Some("~/Data/**".to_string()),
+                ..ScopeRule::default()
+            }],
             fallback_reason: "Substantial session with no insights captured. Create a file in \
                 Memory/Insights/ or Memory/Imperatives/ before ending."
                 .to_string(),
@@ -130,21 +491,35 @@ impl Default for Config {
                 Apply the reusability filter below: capture reusable patterns, \
                 let one-off traces compact away."
                 .to_string(),
-            uncaptured_insight_reason:
-                "Uncaptured insights detected. Rule 12: every \u{2605} Insight block \
-                MUST be persisted as a Memory/Insights/ file before ending."
-                    .to_string(),
+            precompact_suffix: "{?topics}\n\nUncaptured topics from this session: \
+                {topics}.{/topics}{?drafts}\n\nInvoke /MemoryCapture now. Candidates under \
+                `precompact_candidates` in {drafts}.{/drafts}"
+                .to_string(),
+            uncaptured_insight_reason: "Uncaptured insights detected: every \u{2605} Insight \
+                block MUST be persisted as a file in {insights_dir} before ending \
+                ({count} uncaptured: {topics})."
+                .to_string(),
             insight_advisory_prompt:
                 "STOP current work. This session has {count} uncaptured \u{2605} Insight(s): \
-                {topics}. These MUST be persisted as Memory/Insights/ files NOW \
+                {topics}. These MUST be persisted as files in {insights_dir} NOW \
                 using the Write tool. Create the insight files, then resume your previous task."
                     .to_string(),
             memory: MemoryConfig::default(),
             journal: JournalConfig::default(),
             commands: CommandsConfig::default(),
             backlog: "Orchestration/Backlog.md".to_string(),
+            promote: PromoteConfig::default(),
+            skills: BTreeMap::new(),
             surface: SurfaceConfig::default(),
+            advisory: AdvisoryConfig::default(),
+            state: StateConfig::default(),
+            capture: CaptureConfig::default(),
             user: UserConfig::default(),
+            module_root: None,
+            layers: Vec::new(),
+            env_overrides: Vec::new(),
+            profile_source: "config".to_string(),
+            issues: Vec::new(),
         }
     }
 }
@@ -171,6 +546,20 @@ impl Default for CommandsConfig {
     fn default() -> Self {
         Self {
             safe_read: "Modules/forge-tlp/bin/safe-read".to_string(),
+            safe_write: "Modules/forge-tlp/bin/safe-write".to_string(),
+        }
+    }
+}
+
+impl Default for PromoteConfig {
+    fn default() -> Self {
+        Self {
+            steering: "Orchestration/Steering".to_string(),
+            skills: "Orchestration/Skills".to_string(),
+            agents: "Orchestration/Agents".to_string(),
+            invariants: "Orchestration/Invariants".to_string(),
+            scripts: "Scratch".to_string(),
+            archive: "Archives/Memory".to_string(),
         }
     }
 }
@@ -184,6 +573,93 @@ impl Default for SurfaceConfig {
             ideas_cutoff_days: 14,
             due_soon_days: 3,
             max_items: 5,
+            on: vec![SurfaceOn::Startup, SurfaceOn::FirstSkill],
+            trigger_skills: [
+                "DailyPlan",
+                "DailyReview",
+                "Log",
+                "Inbox",
+                "BacklogJournals",
+                "JournalStructure",
+                "Timesheet",
+                "WeeklyReview",
+            ]
+            .map(String::from)
+            .to_vec(),
+            session_start_prompt: "Display this surface digest VERBATIM to the user at the \
+                start of your first reply. Do not summarize, reframe, or skip any items."
+                .to_string(),
+            post_tool_use_prompt: "Display this surface digest VERBATIM to the user. \
+                Do not summarize, reframe, or skip any items."
+                .to_string(),
+        }
+    }
+}
+
+impl Default for CaptureConfig {
+    fn default() -> Self {
+        Self {
+            phrases: [
+                "remember that",
+                "remember this",
+                "note for later",
+                "make a note",
+                "don't forget",
+                "keep in mind",
+            ]
+            .map(String::from)
+            .to_vec(),
+            imperative_cues: [
+                "always",
+                "never",
+                "must",
+                "don't",
+                "do not",
+                "from now on",
+                "make sure",
+                "avoid",
+            ]
+            .map(String::from)
+            .to_vec(),
+            idea_cues: ["idea", "what if", "someday", "could we", "maybe we"]
+                .map(String::from)
+                .to_vec(),
+            prompt: "The user asked you to remember this ({kind}): \"{text}\". \
+                Write it as one memory file in {dir} in this turn, then continue."
+                .to_string(),
+            reason: "{count} capture request(s) from the user have no memory file yet. \
+                Write each one before ending:\n{requests}"
+                .to_string(),
+        }
+    }
+}
+
+impl Default for AdvisoryConfig {
+    fn default() -> Self {
+        Self {
+            interval: 25,
+            duration_minutes: 20,
+            insight_threshold: 10,
+            insight_check_every: 10,
+            cooldown_minutes: 30,
+            levels: vec![
+                "Note: {count} \u{2605} Insight(s) not yet captured: {topics}. \
+                 Persist them as files in {insights_dir} at the next natural pause."
+                    .to_string(),
+                "STOP current work. This session has {count} uncaptured \u{2605} Insight(s): \
+                 {topics}. These MUST be persisted as files in {insights_dir} NOW using the \
+                 Write tool. Create the insight files, then resume your previous task."
+                    .to_string(),
+            ],
+        }
+    }
+}
+
+impl Default for StateConfig {
+    fn default() -> Self {
+        Self {
+            dir: String::new(),
+            retention_days: 14,
         }
     }
 }
@@ -197,61 +673,446 @@ impl Config {
             .map_or("Memory/Insights/", |s| s.as_str())
     }
 
-    /// Load config from `{config,defaults}.yaml` in the module root directory.
-    /// Discovery order: `FORGE_MODULE_ROOT` env → `CLAUDE_PLUGIN_ROOT` env →
-    /// binary path self-discovery (target/release/ → 3 levels up).
+    /// Locate the module root directory (see
+    /// [`Config::module_root_with_source`]).
+    pub fn module_root(config_file: Option<&Path>) -> Option<PathBuf> {
+        Self::module_root_with_source(config_file).map(|(root, _)| root)
+    }
+
+    /// Locate the module root directory and name the source that won.
+    /// Discovery order: the directory of the `--config` file → the
+    /// `FORGE_MODULE_ROOT` and `CLAUDE_PLUGIN_ROOT` env vars →
+    /// `$XDG_CONFIG_HOME/forge-reflect` → `<bin>/../..` (a checkout's
+    /// `target/<profile>/`) and `<bin>/../share/forge-reflect` (a package
+    /// prefix), where `<bin>` is the binary's directory with symlinks
+    /// resolved → the compiled-in [`SEARCH_PATH`]
+    /// (`FORGE_REFLECT_BUILD_SEARCH_PATH`). Except for the env vars,
+    /// a candidate counts only when its [`MODULE_MARKER`] names
+    /// `forge-reflect`.
+    pub fn module_root_with_source(config_file: Option<&Path>) -> Option<(PathBuf, &'static str)> {
+        find_module_root(
+            config_file,
+            |name| std::env::var(name).ok().filter(|v| !v.is_empty()),
+            std::env::current_exe().ok().as_deref(),
+        )
+    }
+
+    /// Load config from `{config,defaults}.yaml` in the module root directory
+    /// (see [`Config::module_root_with_source`]).
     /// Returns compiled defaults if no module root is found.
     pub fn load() -> Self {
-        let module_root = std::env::var("FORGE_MODULE_ROOT")
-            .or_else(|_| std::env::var("CLAUDE_PLUGIN_ROOT"))
-            .or_else(|_| {
-                // Binary is at target/release/<name>, module root is 3 levels up
-                std::env::current_exe()
-                    .ok()
-                    .and_then(|exe| exe.parent()?.parent()?.parent().map(Path::to_path_buf))
-                    .filter(|p| p.join("config.yaml").exists() || p.join("defaults.yaml").exists())
-                    .map(|p| p.to_string_lossy().into_owned())
-                    .ok_or(std::env::VarError::NotPresent)
-            });
-
-        let mut config: Self = if let Ok(root) = module_root {
-            let plugin_root = Path::new(&root);
-            let defaults = forge_lib::sidecar::load_yaml_file(&plugin_root.join("defaults.yaml"))
-                .unwrap_or(serde_yaml::Value::Null);
-            let overlay = forge_lib::sidecar::load_yaml_file(&plugin_root.join("config.yaml"))
-                .unwrap_or(serde_yaml::Value::Null);
-            let merged = forge_lib::sidecar::merge_values(defaults, overlay);
-            serde_yaml::from_value(merged).unwrap_or_else(|e| {
-                eprintln!("forge-reflect: {e}, using defaults");
+        Self::load_with(None, "")
+    }
+
+    /// Like [`Config::load`], but an explicit `config_file` (from `--config`)
+    /// replaces the module's `config.yaml` as the overlay on `defaults.yaml`,
+    /// the [`project_layers`] of `cwd` are merged on top, and the
+    /// [`env_layer`] last.
+    pub fn load_with(config_file: Option<&Path>, cwd: &str) -> Self {
+        let found = Self::module_root_with_source(config_file);
+        let module_root = found.as_ref().map(|(root, _)| root.clone());
+
+        let mut candidates = Vec::new();
+        if let Some(root) = &module_root {
+            candidates.push(root.join("defaults.yaml"));
+        }
+        match (config_file, &module_root) {
+            (Some(file), _) => candidates.push(file.to_path_buf()),
+            (None, Some(root)) => candidates.push(root.join("config.yaml")),
+            (None, None) => {}
+        }
+        let mut layers: Vec<PathBuf> = candidates.into_iter().filter(|p| p.is_file()).collect();
+        let home = std::env::var("HOME").ok().filter(|h| !h.is_empty());
+        layers.extend(project_layers(
+            Path::new(cwd),
+            home.as_deref().map(Path::new),
+        ));
+
+        let (mut env, env_overrides) = env_layer(std::env::vars());
+
+        let mut issues = Vec::new();
+        let mut env_profile = false;
+        let mut config: Self =
+            if layers.is_empty() && env_overrides.is_empty() {
+                if module_root.is_none() {
+                    eprintln!("forge-reflect: module root not found, using defaults");
+                }
                 Self::default()
-            })
-        } else {
-            eprintln!("forge-reflect: module root not found, using defaults");
-            Self::default()
-        };
+            } else {
+                let mut merged = serde_yaml::Value::Null;
+                for path in &layers {
+                    let source = path.display().to_string();
+                    let mut layer = match fs::read_to_string(path)
+                        .map_err(|e| e.to_string())
+                        .and_then(|c| serde_yaml::from_str(&c).map_err(|e| e.to_string()))
+                    {
+                        Ok(layer) => layer,
+                        Err(error) => {
+                            issues.push(ConfigIssue {
+                                source,
+                                issue: Issue::Invalid {
+                                    key: "(file)".to_string(),
+                                    error: format!("unreadable or not YAML: {error}"),
+                                },
+                            });
+                            continue;
+                        }
+                    };
+                    issues.extend(validate::check_layer(&mut layer).into_iter().map(|issue| {
+                        ConfigIssue {
+                            source: source.clone(),
+                            issue,
+                        }
+                    }));
+                    merged = forge_lib::sidecar::merge_values(merged, layer);
+                }
+                issues.extend(validate::check_layer(&mut env).into_iter().map(|issue| {
+                    ConfigIssue {
+                        source: "environment".to_string(),
+                        issue,
+                    }
+                }));
+                env_profile = env.get("profile").is_some();
+                let merged = forge_lib::sidecar::merge_values(merged, env);
+                match serde_yaml::from_value::<Self>(merged) {
+                    Ok(mut config) => {
+                        config.layers = layers;
+                        config.env_overrides = env_overrides;
+                        config
+                    }
+                    Err(e) => {
+                        eprintln!("forge-reflect: {e}, using defaults");
+                        Self::default()
+                    }
+                }
+            };
+        config.module_root = found;
+        issues.extend(config.validate().into_iter().map(|issue| ConfigIssue {
+            source: "merged config".to_string(),
+            issue,
+        }));
+        for issue in &issues {
+            eprintln!("forge-reflect: config: {issue}");
+        }
+        config.issues = issues;
 
-        // Resolve relative user.root against $HOME
-        if !config.user.root.is_empty() && !Path::new(&config.user.root).is_absolute() {
-            if let Ok(home) = std::env::var("HOME") {
-                config.user.root = Path::new(&home)
-                    .join(&config.user.root)
-                    .to_string_lossy()
-                    .into_owned();
+        // Resolve relative user.root and user.roots against $HOME
+        if let Ok(home) = std::env::var("HOME") {
+            let roots = config.user.roots.iter_mut().map(|root| &mut root.path);
+            for root in std::iter::once(&mut config.user.root).chain(roots) {
+                if !root.is_empty() && !Path::new(root.as_str()).is_absolute() {
+                    *root = Path::new(&home).join(&*root).to_string_lossy().into_owned();
+                }
             }
         }
 
+        // Profile precedence: the environment (one session), then the scope
+        // rule matching cwd, then `profile`.
+        let scope = crate::scope::evaluate(&config.scope, cwd, home.as_deref().unwrap_or_default());
+        if env_profile {
+            config.profile_source = format!("${ENV_PREFIX}PROFILE");
+        } else if let (Some(profile), Some((index, _))) = (scope.profile, &scope.rule) {
+            config.profile = profile;
+            config.profile_source = format!("scope rule {index}");
+        }
+
         config
     }
 
-    /// Resolve a user-content path against `user.root` (or cwd fallback).
+    /// JSON Schema for config files: field doc comments as descriptions,
+    /// compiled defaults as `default`. Checked in as [`SCHEMA_FILE`].
+    pub fn schema() -> serde_json::Value {
+        schemars::schema_for!(Config).to_value()
+    }
+
+    /// The value at a dotted key path (`surface.max_items`), as YAML. None
+    /// when no such key exists.
+    pub fn get(&self, key: &str) -> Option<serde_yaml::Value> {
+        let value = serde_yaml::to_value(self).ok()?;
+        key.split('.')
+            .try_fold(&value, |value, part| value.get(part))
+            .cloned()
+    }
+
+    /// Resolve a user-content path against the vault `cwd` routes to (see
+    /// [`UserConfig::roots`]), `user.root` or the cwd.
     pub fn resolve_user_path(&self, cwd: &str, relative: &str) -> PathBuf {
-        if Path::new(relative).is_absolute() {
-            return PathBuf::from(relative);
+        self.route(cwd, None, &[]).join(relative)
+    }
+
+    /// The memory directory for a `kind` note tagged `tags`, in the vault
+    /// routing picks for it.
+    pub fn resolve_memory_dir(&self, cwd: &str, kind: Kind, tags: &[&str]) -> PathBuf {
+        self.route(cwd, Some(kind), tags)
+            .join(kind.dir(&self.memory))
+    }
+
+    fn route(&self, cwd: &str, kind: Option<Kind>, tags: &[&str]) -> PathBuf {
+        let home = std::env::var("HOME").unwrap_or_default();
+        match self
+            .user
+            .roots
+            .iter()
+            .find(|root| root.routes(cwd, kind, tags, &home))
+        {
+            Some(root) => PathBuf::from(&root.path),
+            None if self.user.root.is_empty() => PathBuf::from(cwd),
+            None => PathBuf::from(&self.user.root),
         }
-        if self.user.root.is_empty() {
-            Path::new(cwd).join(relative)
+    }
+
+    /// `relative` in every vault: `user.root` (or the cwd), then each of
+    /// `user.roots`. Absolute paths are returned as they are.
+    pub fn resolve_user_paths(&self, cwd: &str, relative: &str) -> Vec<PathBuf> {
+        let default = if self.user.root.is_empty() {
+            cwd
         } else {
-            Path::new(&self.user.root).join(relative)
+            &self.user.root
+        };
+        let mut paths: Vec<PathBuf> = Vec::new();
+        for root in std::iter::once(default).chain(self.user.roots.iter().map(|r| r.path.as_str()))
+        {
+            let path = Path::new(root).join(relative);
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+        paths
+    }
+
+    /// What a written `path` counts as: the `memory_paths` fragment it
+    /// contains or, failing that, the vault memory directory it is in — and
+    /// whether it is an insight file. None = not a memory write.
+    pub fn memory_write(&self, path: &str) -> Option<(String, bool)> {
+        if let Some(fragment) = self.memory_paths.iter().find(|f| path.contains(f.as_str())) {
+            return Some((fragment.clone(), path.contains(self.insights_path())));
+        }
+        let vaults = std::iter::once(self.user.root.as_str())
+            .chain(self.user.roots.iter().map(|r| r.path.as_str()))
+            .filter(|root| Path::new(root).is_absolute());
+        for root in vaults {
+            for kind in Kind::ALL {
+                let dir = Path::new(root).join(kind.dir(&self.memory));
+                if Path::new(path).starts_with(&dir) {
+                    return Some((dir.display().to_string(), kind == Kind::Insight));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// [`Config::schema`], as shipped in the module root for editors.
+pub const SCHEMA_FILE: &str = "config.schema.json";
+
+/// File that marks a module root: the module manifest, when its `name` is
+/// `forge-reflect`.
+pub const MODULE_MARKER: &str = "module.yaml";
+
+/// Module roots tried when no other source finds one, `:`-separated: the
+/// usual package prefixes, after the source checkout in debug builds. Set
+/// `FORGE_REFLECT_BUILD_SEARCH_PATH` at build time to replace it, e.g. with
+/// the store path of a Nix package.
+pub const SEARCH_PATH: &str = match option_env!("FORGE_REFLECT_BUILD_SEARCH_PATH") {
+    Some(path) => path,
+    None if cfg!(debug_assertions) => concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        ":/usr/local/share/forge-reflect:/opt/homebrew/share/forge-reflect:/usr/share/forge-reflect"
+    ),
+    None => {
+        "/usr/local/share/forge-reflect:/opt/homebrew/share/forge-reflect:/usr/share/forge-reflect"
+    }
+};
+
+/// `dir` holds a [`MODULE_MARKER`] naming this module.
+fn is_module_root(dir: &Path) -> bool {
+    fs::read_to_string(dir.join(MODULE_MARKER))
+        .ok()
+        .and_then(|text| serde_yaml::from_str::<serde_yaml::Value>(&text).ok())
+        .is_some_and(|manifest| manifest["name"].as_str() == Some("forge-reflect"))
+}
+
+/// [`Config::module_root_with_source`] over an explicit environment (`var`)
+/// and binary path.
+pub fn find_module_root(
+    config_file: Option<&Path>,
+    var: impl Fn(&str) -> Option<String>,
+    exe: Option<&Path>,
+) -> Option<(PathBuf, &'static str)> {
+    if let Some(dir) = config_file
+        .and_then(|file| fs::canonicalize(file).ok())
+        .and_then(|file| file.parent().map(Path::to_path_buf))
+        .filter(|dir| is_module_root(dir))
+    {
+        return Some((dir, "--config"));
+    }
+    for name in ["FORGE_MODULE_ROOT", "CLAUDE_PLUGIN_ROOT"] {
+        if let Some(root) = var(name) {
+            return Some((PathBuf::from(root), name));
+        }
+    }
+    let config_home = var("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| var("HOME").map(|home| Path::new(&home).join(".config")));
+    if let Some(dir) = config_home
+        .map(|home| home.join("forge-reflect"))
+        .filter(|dir| is_module_root(dir))
+    {
+        return Some((dir, "XDG_CONFIG_HOME"));
+    }
+    // `cargo build` leaves the binary in <root>/target/<profile>/, packages
+    // put it in <prefix>/bin/ next to <prefix>/share/forge-reflect/.
+    if let Some(bin) = exe
+        .and_then(|exe| fs::canonicalize(exe).ok())
+        .and_then(|exe| exe.parent().map(Path::to_path_buf))
+    {
+        let checkout = bin.parent().and_then(Path::parent).map(Path::to_path_buf);
+        let prefix = bin
+            .parent()
+            .map(|prefix| prefix.join("share/forge-reflect"));
+        if let Some(dir) = checkout
+            .into_iter()
+            .chain(prefix)
+            .find(|dir| is_module_root(dir))
+        {
+            return Some((dir, "binary path"));
+        }
+    }
+    SEARCH_PATH
+        .split(':')
+        .map(PathBuf::from)
+        .find(|dir| is_module_root(dir))
+        .map(|dir| (dir, "search list"))
+}
+
+/// Per-project overlay file, looked up in `cwd` and its ancestors.
+pub const PROJECT_FILE: &str = ".forge-reflect.yaml";
+
+/// [`PROJECT_FILE`]s from `cwd` up to `home` (inclusive), outermost first so
+/// a subdirectory overrides its repository. A `cwd` outside `home` (or
+/// with no `home`), empty or relative has no project layers — shared
+/// directories such as `/tmp` are not trusted to configure the hooks.
+pub fn project_layers(cwd: &Path, home: Option<&Path>) -> Vec<PathBuf> {
+    let Some(home) = home.filter(|home| cwd.is_absolute() && cwd.starts_with(home)) else {
+        return Vec::new();
+    };
+    let mut layers = Vec::new();
+    for dir in cwd.ancestors() {
+        let file = dir.join(PROJECT_FILE);
+        if file.is_file() {
+            layers.push(file);
+        }
+        if dir == home {
+            break;
+        }
+    }
+    layers.reverse();
+    layers
+}
+
+/// Prefix of environment variables that override config keys.
+pub const ENV_PREFIX: &str = "FORGE_REFLECT_";
+
+/// Build-time variables that share [`ENV_PREFIX`] but are not config keys.
+const BUILD_ENV: [&str; 1] = ["FORGE_REFLECT_BUILD_SEARCH_PATH"];
+
+/// Variables the hook scripts read before `FORGE_REFLECT_*` existed, the
+/// keys they stand for and the divisor into the key's unit
+/// (`FORGE_DURATION_THRESHOLD` was in seconds).
+const LEGACY_ENV: [(&str, &str, u64); 3] = [
+    ("FORGE_ADVISORY_INTERVAL", "advisory.interval", 1),
+    ("FORGE_INSIGHT_THRESHOLD", "advisory.insight_threshold", 1),
+    ("FORGE_DURATION_THRESHOLD", "advisory.duration_minutes", 60),
+];
+
+/// Config overrides from environment variables, as a YAML layer plus the
+/// variable names used. `FORGE_REFLECT_<PATH>` sets the key at `<PATH>`,
+/// lowercased with `__` between nesting levels
+/// (`FORGE_REFLECT_SURFACE__MAX_ITEMS=3` → `surface.max_items: 3`). Values
+/// parse as YAML scalars or flow collections (`[a, b]`), else as strings.
+/// A `FORGE_REFLECT_*` variable wins over its legacy alias; the
+/// `FORGE_REFLECT_BUILD_*` variables are read by the compiler, not here.
+pub fn env_layer(
+    vars: impl IntoIterator<Item = (String, String)>,
+) -> (serde_yaml::Value, Vec<String>) {
+    let mut vars: Vec<(String, String)> = vars
+        .into_iter()
+        .filter(|(name, _)| {
+            (name.strip_prefix(ENV_PREFIX).is_some_and(|p| !p.is_empty())
+                && !BUILD_ENV.contains(&name.as_str()))
+                || LEGACY_ENV.iter().any(|(legacy, ..)| legacy == name)
+        })
+        .collect();
+    // Legacy aliases first so the prefixed spelling overrides them; then by
+    // name so the result does not depend on environment order.
+    vars.sort_by_key(|(name, _)| (!LEGACY_ENV.iter().any(|(l, ..)| l == name), name.clone()));
+
+    let mut layer = serde_yaml::Value::Null;
+    let mut names = Vec::new();
+    for (name, mut raw) in vars {
+        let path = match LEGACY_ENV.iter().find(|(legacy, ..)| *legacy == name) {
+            Some((_, key, divisor)) => {
+                if let Ok(value) = raw.trim().parse::<u64>() {
+                    raw = (value / divisor).to_string();
+                }
+                key.split('.').map(str::to_string).collect::<Vec<_>>()
+            }
+            None => name[ENV_PREFIX.len()..]
+                .to_lowercase()
+                .split("__")
+                .map(str::to_string)
+                .collect(),
+        };
+        let value = match serde_yaml::from_str::<serde_yaml::Value>(&raw) {
+            Ok(serde_yaml::Value::Null) | Err(_) => serde_yaml::Value::String(raw),
+            Ok(value) => value,
+        };
+        let nested = path.iter().rev().fold(value, |acc, key| {
+            let mut map = serde_yaml::Mapping::new();
+            map.insert(serde_yaml::Value::String(key.clone()), acc);
+            serde_yaml::Value::Mapping(map)
+        });
+        layer = forge_lib::sidecar::merge_values(layer, nested);
+        names.push(name);
+    }
+    (layer, names)
+}
+
+/// Dotted paths of keys in a config YAML document that no `Config` field
+/// consumes. Nested groups are checked recursively; list values and
+/// free-form maps are not.
+pub fn unknown_keys(value: &serde_yaml::Value) -> Vec<String> {
+    let known = serde_yaml::to_value(Config::default()).unwrap_or(serde_yaml::Value::Null);
+    let mut unknown = Vec::new();
+    collect_unknown(value, &known, "", &mut unknown);
+    unknown
+}
+
+fn collect_unknown(
+    value: &serde_yaml::Value,
+    known: &serde_yaml::Value,
+    prefix: &str,
+    unknown: &mut Vec<String>,
+) {
+    let (Some(map), Some(known_map)) = (value.as_mapping(), known.as_mapping()) else {
+        return;
+    };
+    // An empty group by default is keyed freely (`skills`).
+    if known_map.is_empty() {
+        return;
+    }
+    for (key, child) in map {
+        let name = key
+            .as_str()
+            .map_or_else(|| format!("{key:?}"), str::to_string);
+        let path = if prefix.is_empty() {
+            name
+        } else {
+            format!("{prefix}.{name}")
+        };
+        match known_map.get(key) {
+            Some(known_child) => collect_unknown(child, known_child, &path, unknown),
+            None => unknown.push(path),
         }
     }
 }
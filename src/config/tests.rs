@@ -1,11 +1,12 @@
 use super::*;
+use std::fs;
 
 #[test]
 fn test_default_values() {
     let config = Config::default();
     assert_eq!(config.insight_marker, "\u{2605} Insight");
     assert_eq!(config.memory_paths.len(), 2);
-    assert_eq!(config.write_tool_names.len(), 5);
+    assert_eq!(config.write_tool_names.len(), 8);
     assert!(config.write_tool_names.iter().any(|name| name == "Write"));
     assert!(config
         .write_tool_names
@@ -73,7 +74,7 @@ fn test_load_without_env_var_gives_defaults() {
 
 #[test]
 fn test_nested_yaml_deserialization() {
-    let yaml = r#"
+    let yaml = r"
 memory:
   imperatives: custom/imperatives
   insights: custom/insights
@@ -83,7 +84,7 @@ journal:
 commands:
   safe_read: custom/safe-read
 backlog: custom/backlog.md
-"#;
+";
     let config: Config = serde_yaml::from_str(yaml).unwrap();
     assert_eq!(config.memory.imperatives, "custom/imperatives");
     assert_eq!(config.memory.insights, "custom/insights");
@@ -106,23 +107,55 @@ fn test_duration_threshold_from_yaml() {
 }
 
 #[test]
-fn test_precompact_agent_default() {
+fn test_profile_default_and_deserialization() {
     let config = Config::default();
-    assert_eq!(config.precompact_agent, Some(false));
+    assert_eq!(config.profile, Profile::Strict);
+    assert!(config.profile.blocks() && !config.precompact_agent);
+
+    let config: Config = serde_yaml::from_str("profile: audit-only\n").unwrap();
+    assert_eq!(config.profile, Profile::AuditOnly);
+    assert!(config.profile.blocks() && config.profile.audits());
+
+    let config: Config = serde_yaml::from_str("profile: silent\n").unwrap();
+    assert!(!config.profile.enforces() && !config.profile.blocks());
 }
 
 #[test]
-fn test_precompact_agent_deserialization() {
-    let yaml = "precompact_agent: true\n";
-    let config: Config = serde_yaml::from_str(yaml).unwrap();
-    assert_eq!(config.precompact_agent, Some(true));
+fn test_retired_blocking_switch_off_reads_as_advisory() {
+    let mut layer: serde_yaml::Value =
+        serde_yaml::from_str("insight_blocking: false\nreflect_blocking: true\n").unwrap();
+    let issues = validate::check_layer(&mut layer);
+    assert_eq!(issues.len(), 2);
+    assert!(issues
+        .iter()
+        .all(|issue| matches!(issue, Issue::Retired { .. })));
+    let config: Config = serde_yaml::from_value(layer).unwrap();
+    assert_eq!(config.profile, Profile::Advisory);
+
+    // A profile in the same layer wins.
+    let mut layer: serde_yaml::Value =
+        serde_yaml::from_str("insight_blocking: false\nprofile: strict\n").unwrap();
+    validate::check_layer(&mut layer);
+    assert_eq!(layer["profile"], "strict");
+    assert!(layer.get("insight_blocking").is_none());
 }
 
 #[test]
-fn test_precompact_agent_missing_gives_default() {
-    let yaml = "insight_blocking: true\n";
-    let config: Config = serde_yaml::from_str(yaml).unwrap();
-    assert_eq!(config.precompact_agent, Some(false));
+fn test_retired_data_dir_suffix_reads_as_scope() {
+    let mut layer: serde_yaml::Value = serde_yaml::from_str("data_dir_suffix: Work\n").unwrap();
+    let issues = validate::check_layer(&mut layer);
+    assert!(matches!(
+        issues.as_slice(),
+        [Issue::Retired { key, .. }] if key == "data_dir_suffix"
+    ));
+    let config: Config = serde_yaml::from_value(layer).unwrap();
+    assert_eq!(config.scope.len(), 1);
+    assert_eq!(config.scope[0].include.as_deref(), Some("~/Work/**"));
+
+    let mut layer: serde_yaml::Value =
+        serde_yaml::from_str("data_dir_suffix: Work\nscope: [{include: ~/src/**}]\n").unwrap();
+    validate::check_layer(&mut layer);
+    assert_eq!(layer["scope"][0]["include"], "~/src/**");
 }
 
 #[test]
@@ -156,3 +189,481 @@ fn test_resolve_user_path_absolute_passes_through() {
     let result = config.resolve_user_path("/home/user/data", "/absolute/path");
     assert_eq!(result, std::path::PathBuf::from("/absolute/path"));
 }
+
+fn two_vaults() -> Config {
+    let mut config = Config::default();
+    config.user.root = "/vaults/personal".to_string();
+    config.user.roots = serde_yaml::from_str(
+        "- { name: work, path: /vaults/work, cwd: [/src/work/**] }\n\
+         - { name: team, path: /vaults/team, types: [imperative] }\n\
+         - { name: tagged, path: /vaults/team, tags: [team] }\n\
+         - { name: archive, path: /vaults/archive }\n",
+    )
+    .unwrap();
+    config
+}
+
+#[test]
+fn test_user_roots_route_by_cwd_type_and_tag() {
+    let config = two_vaults();
+    let path = |p: &str| PathBuf::from(p);
+    assert_eq!(
+        config.resolve_user_path("/src/work/app", "Orchestration/Backlog.md"),
+        path("/vaults/work/Orchestration/Backlog.md")
+    );
+    assert_eq!(
+        config.resolve_user_path("/src/home", "Orchestration/Backlog.md"),
+        path("/vaults/personal/Orchestration/Backlog.md")
+    );
+    assert_eq!(
+        config.resolve_memory_dir("/src/home", Kind::Imperative, &[]),
+        path("/vaults/team/Orchestration/Memory/Imperatives")
+    );
+    assert_eq!(
+        config.resolve_memory_dir("/src/home", Kind::Insight, &["team"]),
+        path("/vaults/team/Orchestration/Memory/Insights")
+    );
+    assert_eq!(
+        config.resolve_memory_dir("/src/home", Kind::Insight, &["misc"]),
+        path("/vaults/personal/Orchestration/Memory/Insights")
+    );
+    assert_eq!(
+        config.resolve_user_paths("/src/home", "Orchestration/Backlog.md"),
+        ["personal", "work", "team", "archive"]
+            .map(|v| path(&format!("/vaults/{v}/Orchestration/Backlog.md")))
+    );
+}
+
+#[test]
+fn test_memory_write_credits_every_vault() {
+    let mut config = two_vaults();
+    config.memory.ideas = "Someday".to_string();
+    assert_eq!(
+        config.memory_write("/vaults/archive/Someday/x.md"),
+        Some(("/vaults/archive/Someday".to_string(), false))
+    );
+    assert_eq!(
+        config.memory_write("/tmp/Memory/Insights/x.md"),
+        Some(("Memory/Insights/".to_string(), true))
+    );
+    assert_eq!(config.memory_write("/tmp/Someday/x.md"), None);
+}
+
+#[test]
+fn test_validate_rejects_bad_user_roots() {
+    let mut config = two_vaults();
+    config.user.roots[1].name = "work".to_string();
+    let issues = config.validate();
+    assert_eq!(issues.len(), 1);
+    assert!(issues[0].to_string().contains("`user.roots` repeat a name"));
+    assert!(config.user.roots.is_empty());
+    assert!(two_vaults().validate().is_empty());
+}
+
+#[test]
+fn test_validate_rejects_relative_state_dir() {
+    for dir in ["~/.local/state/forge-reflect", "state"] {
+        let mut config = Config::default();
+        config.state.dir = dir.to_string();
+        let issues = config.validate();
+        assert_eq!(issues.len(), 1, "{dir}");
+        assert!(issues[0].to_string().contains("`state.dir`"));
+        assert!(config.state.dir.is_empty());
+    }
+    let mut config = Config::default();
+    config.state.dir = "/var/lib/forge-reflect".to_string();
+    assert!(config.validate().is_empty());
+}
+
+#[test]
+fn test_unknown_keys_reports_dotted_paths() {
+    let yaml: serde_yaml::Value = serde_yaml::from_str(
+        "tool_turn_treshold: 5\nsurface:\n  max_items: 3\n  max_itmes: 4\nmemory_paths: [a]\n",
+    )
+    .unwrap();
+    assert_eq!(
+        unknown_keys(&yaml),
+        vec!["tool_turn_treshold", "surface.max_itmes"]
+    );
+}
+
+#[test]
+fn test_unknown_keys_empty_for_known_config() {
+    let yaml: serde_yaml::Value =
+        serde_yaml::from_str("profile: advisory\nuser:\n  root: /vault\n").unwrap();
+    assert!(unknown_keys(&yaml).is_empty());
+}
+
+#[test]
+fn test_surface_on_from_source() {
+    assert_eq!(SurfaceOn::from_source("compact"), SurfaceOn::Compact);
+    for source in ["startup", "resume", "clear", ""] {
+        assert_eq!(SurfaceOn::from_source(source), SurfaceOn::Startup);
+    }
+    let config: Config = serde_yaml::from_str("surface:\n  on: [first_skill, compact]\n").unwrap();
+    assert_eq!(
+        config.surface.on,
+        vec![SurfaceOn::FirstSkill, SurfaceOn::Compact]
+    );
+    assert_eq!(
+        Config::default().surface.on,
+        vec![SurfaceOn::Startup, SurfaceOn::FirstSkill]
+    );
+}
+
+#[test]
+fn test_project_layers_walk_up_to_home() {
+    let home = tempfile::tempdir().unwrap();
+    let repo = home.path().join("repo");
+    let sub = repo.join("crates/sub");
+    fs::create_dir_all(&sub).unwrap();
+    for dir in [home.path(), repo.as_path(), sub.as_path()] {
+        fs::write(dir.join(PROJECT_FILE), "{}").unwrap();
+    }
+
+    let layers = project_layers(&sub, Some(home.path()));
+    assert_eq!(
+        layers,
+        vec![
+            home.path().join(PROJECT_FILE),
+            repo.join(PROJECT_FILE),
+            sub.join(PROJECT_FILE),
+        ]
+    );
+    assert_eq!(
+        project_layers(&repo, Some(&repo)),
+        vec![repo.join(PROJECT_FILE)]
+    );
+    assert!(project_layers(Path::new(""), Some(home.path())).is_empty());
+    assert!(project_layers(&sub, None).is_empty());
+}
+
+#[test]
+fn test_project_layers_none_outside_home() {
+    let home = tempfile::tempdir().unwrap();
+    let elsewhere = tempfile::tempdir().unwrap();
+    let cwd = elsewhere.path().join("shared");
+    fs::create_dir_all(&cwd).unwrap();
+    for dir in [elsewhere.path(), cwd.as_path()] {
+        fs::write(dir.join(PROJECT_FILE), "{}").unwrap();
+    }
+
+    assert!(project_layers(&cwd, Some(home.path())).is_empty());
+}
+
+#[test]
+fn test_find_module_root_sources_in_order() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = |name: &str| {
+        let dir = tmp.path().join(name);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(MODULE_MARKER), "name: forge-reflect\n").unwrap();
+        fs::canonicalize(dir).unwrap()
+    };
+    let flagged = root("flagged");
+    fs::write(flagged.join("config.yaml"), "{}").unwrap();
+    let xdg = root("xdg/forge-reflect");
+    let vars = |name: &str| match name {
+        "FORGE_MODULE_ROOT" => Some("/from/env".to_string()),
+        "XDG_CONFIG_HOME" => Some(tmp.path().join("xdg").display().to_string()),
+        _ => None,
+    };
+
+    let config_file = flagged.join("config.yaml");
+    let found = find_module_root(Some(&config_file), vars, None);
+    assert_eq!(found, Some((flagged, "--config")));
+    // A --config file outside any module root falls through.
+    let loose = tmp.path().join("loose.yaml");
+    fs::write(&loose, "{}").unwrap();
+    let found = find_module_root(Some(&loose), vars, None);
+    assert_eq!(
+        found,
+        Some((PathBuf::from("/from/env"), "FORGE_MODULE_ROOT"))
+    );
+    let no_env = |name: &str| vars(name).filter(|_| name != "FORGE_MODULE_ROOT");
+    assert_eq!(
+        find_module_root(None, no_env, None),
+        Some((xdg, "XDG_CONFIG_HOME"))
+    );
+}
+
+#[test]
+fn test_find_module_root_from_binary_layouts() {
+    let tmp = tempfile::tempdir().unwrap();
+    let tmp_path = fs::canonicalize(tmp.path()).unwrap();
+    let no_vars = |_: &str| None;
+
+    // Checkout: <root>/target/release/forge-reflect, reached via a symlink.
+    let checkout = tmp_path.join("checkout");
+    fs::create_dir_all(checkout.join("target/release")).unwrap();
+    fs::write(checkout.join(MODULE_MARKER), "name: forge-reflect\n").unwrap();
+    let exe = checkout.join("target/release/forge-reflect");
+    fs::write(&exe, "").unwrap();
+    let link = tmp_path.join("forge-reflect");
+    std::os::unix::fs::symlink(&exe, &link).unwrap();
+    assert_eq!(
+        find_module_root(None, no_vars, Some(&link)),
+        Some((checkout.clone(), "binary path"))
+    );
+
+    // Package prefix: <prefix>/bin/forge-reflect, <prefix>/share/forge-reflect.
+    let prefix = tmp_path.join("prefix");
+    let share = prefix.join("share/forge-reflect");
+    fs::create_dir_all(&share).unwrap();
+    fs::create_dir_all(prefix.join("bin")).unwrap();
+    fs::write(share.join(MODULE_MARKER), "name: forge-reflect\n").unwrap();
+    let exe = prefix.join("bin/forge-reflect");
+    fs::write(&exe, "").unwrap();
+    assert_eq!(
+        find_module_root(None, no_vars, Some(&exe)),
+        Some((share.clone(), "binary path"))
+    );
+
+    // Another module's manifest, or one further up, does not count.
+    fs::write(share.join(MODULE_MARKER), "name: forge-journal\n").unwrap();
+    let nested = checkout.join("target/release/deps");
+    fs::create_dir_all(&nested).unwrap();
+    fs::write(nested.join("forge-reflect"), "").unwrap();
+    for exe in [exe, nested.join("forge-reflect")] {
+        assert_ne!(
+            find_module_root(None, no_vars, Some(&exe)).map(|(_, source)| source),
+            Some("binary path")
+        );
+    }
+
+    // Nothing else found: the compiled-in list, which starts at the
+    // checkout in debug builds.
+    if cfg!(debug_assertions) {
+        let (root, source) = find_module_root(None, no_vars, None).unwrap();
+        assert_eq!(source, "search list");
+        assert_eq!(root, Path::new(env!("CARGO_MANIFEST_DIR")));
+    }
+}
+
+fn env(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs
+        .iter()
+        .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+        .collect()
+}
+
+#[test]
+fn test_layers_merge_with_the_sidecar_semantics() {
+    let yaml = |text: &str| serde_yaml::from_str::<serde_yaml::Value>(text).unwrap();
+    let base = yaml(
+        "surface:\n  max_items: 5\n  ideas_cutoff_days: 30\nstop_gates: [insight, reflect]\nbacklog: a.md\n",
+    );
+    // Mappings merge key by key, sequences are replaced whole, and a null
+    // keeps the value below it.
+    let merged = forge_lib::sidecar::merge_values(
+        base.clone(),
+        yaml("surface:\n  max_items: 3\nstop_gates: [capture]\nbacklog: null\n"),
+    );
+    assert_eq!(
+        merged,
+        yaml(
+            "surface:\n  max_items: 3\n  ideas_cutoff_days: 30\nstop_gates: [capture]\nbacklog: a.md\n"
+        )
+    );
+    // A layer `check_layer` emptied to null leaves the stack unchanged.
+    assert_eq!(
+        forge_lib::sidecar::merge_values(base.clone(), serde_yaml::Value::Null),
+        base
+    );
+}
+
+#[test]
+fn test_env_layer_nests_and_types_values() {
+    let (layer, names) = env_layer(env(&[
+        ("FORGE_REFLECT_SURFACE__MAX_ITEMS", "3"),
+        ("FORGE_REFLECT_TOOL_TURN_THRESHOLD", "20"),
+        ("FORGE_REFLECT_STOP_GATES", "[insight]"),
+        ("FORGE_REFLECT_BACKLOG", "Notes/Backlog.md"),
+        (
+            "FORGE_REFLECT_BUILD_SEARCH_PATH",
+            "/nix/store/x/share/forge-reflect",
+        ),
+        ("HOME", "/Users/test"),
+    ]));
+    assert_eq!(names.len(), 4);
+    assert!(!names.iter().any(|name| name.contains("BUILD")));
+    let config: Config = serde_yaml::from_value(layer).unwrap();
+    assert_eq!(config.surface.max_items, 3);
+    assert_eq!(config.tool_turn_threshold, 20);
+    assert_eq!(config.stop_gates, vec![StopGate::Insight]);
+    assert_eq!(config.backlog, "Notes/Backlog.md");
+    // Untouched keys keep their defaults.
+    assert_eq!(config.user_msg_threshold, 4);
+}
+
+#[test]
+fn test_env_layer_legacy_alias_loses_to_prefixed() {
+    let (layer, names) = env_layer(env(&[
+        ("FORGE_REFLECT_ADVISORY__INTERVAL", "40"),
+        ("FORGE_ADVISORY_INTERVAL", "5"),
+        ("FORGE_INSIGHT_THRESHOLD", "7"),
+    ]));
+    assert_eq!(
+        names,
+        vec![
+            "FORGE_ADVISORY_INTERVAL",
+            "FORGE_INSIGHT_THRESHOLD",
+            "FORGE_REFLECT_ADVISORY__INTERVAL"
+        ]
+    );
+    let config: Config = serde_yaml::from_value(layer).unwrap();
+    assert_eq!(config.advisory.interval, 40);
+    assert_eq!(config.advisory.insight_threshold, 7);
+}
+
+#[test]
+fn test_env_layer_legacy_duration_is_in_seconds() {
+    let (layer, _) = env_layer(env(&[("FORGE_DURATION_THRESHOLD", "1200")]));
+    let config: Config = serde_yaml::from_value(layer).unwrap();
+    assert_eq!(config.advisory.duration_minutes, 20);
+}
+
+#[test]
+fn test_check_layer_drops_only_the_bad_field() {
+    let mut layer: serde_yaml::Value = serde_yaml::from_str(
+        "tool_turn_threshold: many\nuser_msg_threshold: 6\nsurface:\n  max_items: lots\n  reminders_list: tasks\n",
+    )
+    .unwrap();
+    let issues = validate::check_layer(&mut layer);
+    let keys: Vec<&str> = issues.iter().map(Issue::key).collect();
+    assert_eq!(keys, vec!["tool_turn_threshold", "surface.max_items"]);
+    let config: Config = serde_yaml::from_value(layer).unwrap();
+    assert_eq!(config.tool_turn_threshold, 10);
+    assert_eq!(config.user_msg_threshold, 6);
+    assert_eq!(config.surface.max_items, 5);
+    assert_eq!(config.surface.reminders_list, "tasks");
+}
+
+#[test]
+fn test_check_layer_suggests_close_keys() {
+    let mut layer: serde_yaml::Value =
+        serde_yaml::from_str("tool_turn_treshold: 5\nsurface:\n  max_itmes: 3\nzebra: 1\n")
+            .unwrap();
+    let issues: Vec<String> = validate::check_layer(&mut layer)
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(
+        issues,
+        vec![
+            "unknown key `tool_turn_treshold` (did you mean `tool_turn_threshold`?)",
+            "unknown key `surface.max_itmes` (did you mean `surface.max_items`?)",
+            "unknown key `zebra` is ignored",
+        ]
+    );
+}
+
+#[test]
+fn test_check_layer_rejects_non_mapping() {
+    let mut layer = serde_yaml::Value::String("oops".to_string());
+    assert_eq!(validate::check_layer(&mut layer).len(), 1);
+    assert!(layer.is_null());
+}
+
+#[test]
+fn test_validate_resets_out_of_range_values() {
+    let mut config: Config = serde_yaml::from_str(
+        "advisory:\n  interval: 0\nmemory:\n  insights: ../elsewhere\nuser:\n  root: ~/vault\n",
+    )
+    .unwrap();
+    let keys: Vec<String> = config
+        .validate()
+        .iter()
+        .map(|i| i.key().to_string())
+        .collect();
+    assert_eq!(
+        keys,
+        vec!["advisory.interval", "user.root", "memory.insights"]
+    );
+    assert_eq!(config.advisory.interval, 25);
+    assert_eq!(config.memory.insights, "Orchestration/Memory/Insights");
+    assert!(config.user.root.is_empty());
+    assert!(Config::default().validate().is_empty());
+}
+
+#[test]
+fn test_validate_checks_message_placeholders() {
+    let mut config: Config = serde_yaml::from_str(
+        "capture:\n  prompt: \"Save {text} to {folder}\"\nfallback_reason: \"{?duration}Long{/topics}\"\nprecompact_suffix: \"{?drafts}see {drafts}{/drafts}\"\n",
+    )
+    .unwrap();
+    let issues: Vec<String> = config.validate().iter().map(ToString::to_string).collect();
+    assert_eq!(issues.len(), 2);
+    assert!(issues[0].contains("`fallback_reason`"));
+    assert!(issues[0].contains("closes section `topics` that is not open"));
+    assert!(issues[1].contains("`capture.prompt`"));
+    assert!(issues[1].contains("unknown placeholder `{folder}`"));
+    assert_eq!(config.capture.prompt, Config::default().capture.prompt);
+    assert_eq!(config.precompact_suffix, "{?drafts}see {drafts}{/drafts}");
+}
+
+#[test]
+fn test_promote_skills_and_safe_write_are_typed() {
+    let yaml = r"
+commands:
+  safe_write: bin/safe-write
+promote:
+  steering: Rules
+skills:
+  claude:
+    Surface:
+    MemoryIdea:
+      template: skills/MemoryIdea/Template.md
+";
+    let value: serde_yaml::Value = serde_yaml::from_str(yaml).unwrap();
+    assert!(unknown_keys(&value).is_empty());
+    let config: Config = serde_yaml::from_value(value).unwrap();
+    assert_eq!(config.commands.safe_write, "bin/safe-write");
+    assert_eq!(config.promote.steering, "Rules");
+    assert_eq!(config.promote.archive, "Archives/Memory");
+    let claude = &config.skills["claude"];
+    assert_eq!(claude["Surface"], None);
+    assert_eq!(
+        claude["MemoryIdea"].as_ref().unwrap().template.as_deref(),
+        Some("skills/MemoryIdea/Template.md")
+    );
+}
+
+#[test]
+fn test_get_dotted_key() {
+    let config = Config::default();
+    assert_eq!(
+        config.get("surface.max_items"),
+        Some(serde_yaml::Value::from(5))
+    );
+    assert_eq!(
+        config.get("promote.steering"),
+        Some(serde_yaml::Value::from("Orchestration/Steering"))
+    );
+    assert!(config.get("memory").unwrap().is_mapping());
+    assert_eq!(config.get("surface.max_itmes"), None);
+    assert_eq!(config.get("layers"), None);
+}
+
+#[test]
+fn test_schema_matches_checked_in_file() {
+    let schema = Config::schema();
+    let file: serde_json::Value =
+        serde_json::from_str(include_str!("../../config.schema.json")).unwrap();
+    assert!(
+        schema == file,
+        "{SCHEMA_FILE} is stale \u{2014} regenerate with `forge-reflect config schema > {SCHEMA_FILE}`"
+    );
+
+    let surface = &schema["$defs"]["SurfaceConfig"]["properties"];
+    assert_eq!(surface["max_items"]["default"], 5);
+    assert_eq!(
+        surface["max_items"]["description"],
+        "Items shown per digest section."
+    );
+    assert_eq!(
+        schema["properties"]["memory"]["default"]["insights"],
+        "Orchestration/Memory/Insights"
+    );
+    assert_eq!(schema["additionalProperties"], false);
+}
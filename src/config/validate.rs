@@ -0,0 +1,410 @@
+//! Config validation — unknown keys with suggestions, fields of the wrong
+//! type, and values out of range. Problems are reported as [`Issue`]s and
+//! the offending value falls back to its default instead of the whole file.
+
+use super::Config;
+use crate::prompt::template;
+use serde_yaml::{Mapping, Value};
+use std::fmt;
+
+/// One problem with a config layer or the merged config.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Issue {
+    /// No `Config` field consumes `key`; the value is ignored.
+    Unknown {
+        key: String,
+        suggestion: Option<String>,
+    },
+    /// `key` does not deserialize; the layer's value is dropped.
+    Invalid { key: String, error: String },
+    /// `key` deserialized but is unusable; reset to the default.
+    OutOfRange { key: String, problem: String },
+    /// `key` no longer exists; its value was read `as` a current setting.
+    Retired { key: String, read_as: String },
+}
+
+impl Issue {
+    pub fn key(&self) -> &str {
+        match self {
+            Self::Unknown { key, .. }
+            | Self::Invalid { key, .. }
+            | Self::OutOfRange { key, .. }
+            | Self::Retired { key, .. } => key,
+        }
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unknown {
+                key,
+                suggestion: Some(suggestion),
+            } => write!(f, "unknown key `{key}` (did you mean `{suggestion}`?)"),
+            Self::Unknown { key, .. } => write!(f, "unknown key `{key}` is ignored"),
+            Self::Invalid { key, error } => {
+                write!(f, "`{key}`: {error} \u{2014} using the default")
+            }
+            Self::OutOfRange { key, problem } => {
+                write!(f, "`{key}` {problem} \u{2014} using the default")
+            }
+            Self::Retired { key, read_as } => {
+                write!(f, "`{key}` is retired \u{2014} read as {read_as}")
+            }
+        }
+    }
+}
+
+/// Check one YAML layer before it is merged: report unknown keys, and drop
+/// every field that would fail to deserialize so the rest of the layer
+/// still applies. A layer that is not a mapping is replaced by `Null`.
+pub fn check_layer(layer: &mut Value) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    if layer.is_null() {
+        return issues;
+    }
+    if !layer.is_mapping() {
+        issues.push(Issue::Invalid {
+            key: "(root)".to_string(),
+            error: "expected a mapping of config keys".to_string(),
+        });
+        *layer = Value::Null;
+        return issues;
+    }
+    if let Value::Mapping(map) = layer {
+        migrate_retired(map, &mut issues);
+    }
+    prune(layer, &mut Vec::new(), &mut issues);
+    let known = known();
+    for key in super::unknown_keys(layer) {
+        let suggestion = suggest(&key, &known);
+        issues.push(Issue::Unknown { key, suggestion });
+    }
+    issues
+}
+
+/// Rewrite the switches `profile` and `scope` replaced, within the layer
+/// that sets them: `insight_blocking` or `reflect_blocking` false becomes
+/// `profile: advisory`, and `data_dir_suffix: <dir>` a `~/<dir>/**` scope.
+/// A `profile` or `scope` the layer sets itself wins.
+fn migrate_retired(layer: &mut Mapping, issues: &mut Vec<Issue>) {
+    let blocking: Vec<(&str, Value)> = ["insight_blocking", "reflect_blocking"]
+        .into_iter()
+        .filter_map(|key| layer.remove(key).map(|value| (key, value)))
+        .collect();
+    let non_blocking = blocking
+        .iter()
+        .any(|(_, value)| value.as_bool() == Some(false));
+    let profile_set = layer.contains_key("profile");
+    if non_blocking && !profile_set {
+        layer.insert("profile".into(), "advisory".into());
+    }
+    for (key, _) in blocking {
+        let read_as = if profile_set {
+            "the `profile` set alongside it".to_string()
+        } else if non_blocking {
+            "`profile: advisory`".to_string()
+        } else {
+            "`profile` unchanged".to_string()
+        };
+        issues.push(Issue::Retired {
+            key: key.to_string(),
+            read_as,
+        });
+    }
+
+    let Some(suffix) = layer.remove("data_dir_suffix") else {
+        return;
+    };
+    let read_as = match suffix.as_str().map(|s| s.trim_matches('/')) {
+        Some(dir) if !dir.is_empty() && !layer.contains_key("scope") => {
+            let include = format!("~/{dir}/**");
+            let mut rule = Mapping::new();
+            rule.insert("include".into(), include.clone().into());
+            layer.insert("scope".into(), Value::Sequence(vec![Value::Mapping(rule)]));
+            format!("`scope: [{{include: {include}}}]`")
+        }
+        Some(_) if layer.contains_key("scope") => "the `scope` set alongside it".to_string(),
+        _ => "nothing (not a directory name); set `scope`".to_string(),
+    };
+    issues.push(Issue::Retired {
+        key: "data_dir_suffix".to_string(),
+        read_as,
+    });
+}
+
+/// Closest known key to the unknown dotted `key`, among its siblings.
+pub fn suggest(key: &str, known: &Value) -> Option<String> {
+    let (parent, name) = key.rsplit_once('.').unwrap_or(("", key));
+    let siblings = parent
+        .split('.')
+        .filter(|p| !p.is_empty())
+        .try_fold(known, |value, part| value.get(part))?
+        .as_mapping()?;
+    let best = siblings
+        .keys()
+        .filter_map(Value::as_str)
+        .map(|candidate| (distance(name, candidate), candidate))
+        .min()?;
+    // Close enough to be a typo, not a different word.
+    (best.0 <= (name.len() / 3).max(1)).then(|| {
+        if parent.is_empty() {
+            best.1.to_string()
+        } else {
+            format!("{parent}.{}", best.1)
+        }
+    })
+}
+
+/// Drop the fields of the mapping at `path` that do not deserialize on
+/// their own, descending into nested groups so only the bad leaf goes.
+fn prune(value: &mut Value, path: &mut Vec<String>, issues: &mut Vec<Issue>) {
+    let Some(map) = value.as_mapping_mut() else {
+        return;
+    };
+    let keys: Vec<Value> = map.keys().cloned().collect();
+    for key in keys {
+        let Some(name) = key.as_str().map(str::to_string) else {
+            continue;
+        };
+        path.push(name);
+        if let Some(child) = map.get_mut(&key) {
+            if probe(path, child).is_err() {
+                prune(child, path, issues);
+                if let Err(error) = probe(path, child) {
+                    map.remove(&key);
+                    issues.push(Issue::Invalid {
+                        key: path.join("."),
+                        error,
+                    });
+                }
+            }
+        }
+        path.pop();
+    }
+}
+
+/// Deserialize a `Config` holding only `value` at `path`.
+fn probe(path: &[String], value: &Value) -> Result<(), String> {
+    let nested = path.iter().rev().fold(value.clone(), |acc, key| {
+        let mut map = Mapping::new();
+        map.insert(Value::String(key.clone()), acc);
+        Value::Mapping(map)
+    });
+    serde_yaml::from_value::<Config>(nested)
+        .map(drop)
+        .map_err(|e| e.to_string())
+}
+
+fn known() -> Value {
+    serde_yaml::to_value(Config::default()).unwrap_or(Value::Null)
+}
+
+/// Levenshtein distance.
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            let next = (row[j] + 1).min(row[j + 1] + 1).min(prev + cost);
+            prev = row[j + 1];
+            row[j + 1] = next;
+        }
+    }
+    row[b.len()]
+}
+
+impl Config {
+    /// Range and path checks on the merged config. Each failing value is
+    /// reset to its compiled default and reported.
+    pub fn validate(&mut self) -> Vec<Issue> {
+        let defaults = Self::default();
+        let mut issues = Vec::new();
+        let mut out_of_range = |key: &str, problem: &str| {
+            issues.push(Issue::OutOfRange {
+                key: key.to_string(),
+                problem: problem.to_string(),
+            });
+        };
+
+        if self.advisory.interval == 0 {
+            out_of_range("advisory.interval", "must be at least 1");
+            self.advisory.interval = defaults.advisory.interval;
+        }
+        if self.surface.max_items == 0 {
+            out_of_range("surface.max_items", "must be at least 1");
+            self.surface.max_items = defaults.surface.max_items;
+        }
+        if self.insight_marker.trim().is_empty() {
+            out_of_range("insight_marker", "is empty");
+            self.insight_marker = defaults.insight_marker;
+        }
+        if self.memory_paths.is_empty() || self.memory_paths.iter().any(|p| p.trim().is_empty()) {
+            out_of_range("memory_paths", "must list non-empty paths");
+            self.memory_paths = defaults.memory_paths;
+        }
+        if self.user.root.starts_with('~') {
+            out_of_range(
+                "user.root",
+                "starts with `~`, which is not expanded \u{2014} write it relative to $HOME",
+            );
+            self.user.root = defaults.user.root;
+        }
+        if let Some(problem) = self.user.roots.iter().enumerate().find_map(|(i, root)| {
+            if root.name.trim().is_empty() || root.path.trim().is_empty() {
+                Some("need a `name` and a `path` each")
+            } else if root.path.starts_with('~') {
+                Some("have a path starting with `~`, which is not expanded \u{2014} write it relative to $HOME")
+            } else if self.user.roots[..i].iter().any(|r| r.name == root.name) {
+                Some("repeat a name")
+            } else {
+                None
+            }
+        }) {
+            out_of_range("user.roots", problem);
+            self.user.roots = defaults.user.roots;
+        }
+
+        if !self.state.dir.is_empty() && std::path::Path::new(&self.state.dir).is_relative() {
+            out_of_range(
+                "state.dir",
+                "must be an absolute path (`~` is not expanded)",
+            );
+            self.state.dir = defaults.state.dir;
+        }
+
+        let paths: [(&str, &mut String, String); 7] = [
+            (
+                "memory.insights",
+                &mut self.memory.insights,
+                defaults.memory.insights,
+            ),
+            (
+                "memory.imperatives",
+                &mut self.memory.imperatives,
+                defaults.memory.imperatives,
+            ),
+            (
+                "memory.ideas",
+                &mut self.memory.ideas,
+                defaults.memory.ideas,
+            ),
+            (
+                "journal.daily",
+                &mut self.journal.daily,
+                defaults.journal.daily,
+            ),
+            ("backlog", &mut self.backlog, defaults.backlog),
+            ("reflection", &mut self.reflection, defaults.reflection),
+            (
+                "insight_check",
+                &mut self.insight_check,
+                defaults.insight_check,
+            ),
+        ];
+        for (key, value, default) in paths {
+            let problem = if value.trim().is_empty() {
+                "is empty"
+            } else if value.split('/').any(|part| part == "..") {
+                "contains `..` \u{2014} user paths stay inside the user root"
+            } else {
+                continue;
+            };
+            out_of_range(key, problem);
+            *value = default;
+        }
+
+        issues.extend(self.validate_messages());
+        issues
+    }
+
+    /// Placeholder and section checks on the message templates.
+    fn validate_messages(&mut self) -> Vec<Issue> {
+        let defaults = Self::default();
+        let mut issues = Vec::new();
+        let mut out_of_range = |key: &str, problem: String| {
+            issues.push(Issue::OutOfRange {
+                key: key.to_string(),
+                problem,
+            });
+        };
+
+        // Each with the placeholders it takes beyond the session variables.
+        let messages: [(&str, &mut String, String, &[&str]); 9] = [
+            (
+                "fallback_reason",
+                &mut self.fallback_reason,
+                defaults.fallback_reason,
+                &[],
+            ),
+            (
+                "precompact_prefix",
+                &mut self.precompact_prefix,
+                defaults.precompact_prefix,
+                &["drafts"],
+            ),
+            (
+                "precompact_suffix",
+                &mut self.precompact_suffix,
+                defaults.precompact_suffix,
+                &["drafts"],
+            ),
+            (
+                "uncaptured_insight_reason",
+                &mut self.uncaptured_insight_reason,
+                defaults.uncaptured_insight_reason,
+                &[],
+            ),
+            (
+                "insight_advisory_prompt",
+                &mut self.insight_advisory_prompt,
+                defaults.insight_advisory_prompt,
+                &[],
+            ),
+            (
+                "capture.prompt",
+                &mut self.capture.prompt,
+                defaults.capture.prompt,
+                &["kind", "text", "dir"],
+            ),
+            (
+                "capture.reason",
+                &mut self.capture.reason,
+                defaults.capture.reason,
+                &["requests"],
+            ),
+            (
+                "surface.session_start_prompt",
+                &mut self.surface.session_start_prompt,
+                defaults.surface.session_start_prompt,
+                &[],
+            ),
+            (
+                "surface.post_tool_use_prompt",
+                &mut self.surface.post_tool_use_prompt,
+                defaults.surface.post_tool_use_prompt,
+                &[],
+            ),
+        ];
+        for (key, value, default, extra) in messages {
+            let known: Vec<&str> = template::SESSION.iter().chain(extra).copied().collect();
+            if let Err(problem) = template::check(value, &known) {
+                out_of_range(key, problem);
+                *value = default;
+            }
+        }
+        if let Some(problem) = self
+            .advisory
+            .levels
+            .iter()
+            .find_map(|level| template::check(level, &template::SESSION).err())
+        {
+            out_of_range("advisory.levels", problem);
+            self.advisory.levels = defaults.advisory.levels;
+        }
+        issues
+    }
+}
@@ -0,0 +1,512 @@
+//! Installation checks for `forge-reflect doctor`.
+//!
+//! Misconfiguration is silent at hook time — config errors fall back to
+//! defaults and missing paths just mean nothing is found. Each check here
+//! names the problem and the fix.
+
+use crate::config::{self, Config};
+use crate::prompt;
+use crate::transcript;
+use serde::Serialize;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Ok,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Check {
+    pub name: String,
+    pub status: Status,
+    pub detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix: Option<String>,
+}
+
+impl Check {
+    fn ok(name: &str, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            status: Status::Ok,
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    fn warn(name: &str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            status: Status::Warn,
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+
+    fn fail(name: &str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            status: Status::Fail,
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+}
+
+/// Run every check. `config_file` is the `--config` override, if any.
+pub fn run_checks(config: &Config, config_file: Option<&Path>, cwd: &str) -> Vec<Check> {
+    let module_root = config.module_root.clone();
+    let mut checks = vec![check_module_root(module_root.as_ref())];
+
+    if let Some((root, _)) = &module_root {
+        checks.extend(check_config_file(&root.join("defaults.yaml"), true));
+    }
+    match (config_file, &module_root) {
+        (Some(file), _) => checks.extend(check_config_file(file, true)),
+        (None, Some((root, _))) => {
+            checks.extend(check_config_file(&root.join("config.yaml"), false));
+        }
+        (None, None) => {}
+    }
+    for layer in config
+        .layers
+        .iter()
+        .filter(|p| p.file_name().is_some_and(|n| n == config::PROJECT_FILE))
+    {
+        checks.extend(check_config_file(layer, true));
+    }
+    checks.extend(check_env(std::env::vars()));
+    checks.push(check_layers(config));
+    checks.extend(check_values(config));
+    checks.extend(check_scope(config, cwd));
+
+    checks.extend(check_paths(config, cwd));
+    checks.extend(check_skills(config, cwd));
+    checks.extend(check_markers(config));
+    if let Some((root, _)) = &module_root {
+        checks.extend(check_hooks(root));
+    }
+    checks
+}
+
+fn check_module_root(module_root: Option<&(std::path::PathBuf, &'static str)>) -> Check {
+    match module_root {
+        Some((root, source)) if root.is_dir() => {
+            Check::ok("module root", format!("{} (from {source})", root.display()))
+        }
+        Some((root, source)) => Check::fail(
+            "module root",
+            format!("{} (from {source}) is not a directory", root.display()),
+            format!("point {source} at the forge-reflect checkout"),
+        ),
+        None => Check::fail(
+            "module root",
+            "not found \u{2014} compiled defaults in use",
+            "export FORGE_MODULE_ROOT=/path/to/forge-reflect, or copy the module to \
+             $XDG_CONFIG_HOME/forge-reflect",
+        ),
+    }
+}
+
+/// Parse one config layer: YAML syntax, `Config` types, unknown keys.
+/// A missing file fails only when `required`.
+pub fn check_config_file(path: &Path, required: bool) -> Vec<Check> {
+    let name = format!(
+        "config {}",
+        path.file_name()
+            .map_or_else(String::new, |n| n.to_string_lossy().into_owned())
+    );
+    let Ok(content) = fs::read_to_string(path) else {
+        return if required {
+            vec![Check::fail(
+                &name,
+                format!("{} unreadable", path.display()),
+                "check the path passed to --config",
+            )]
+        } else {
+            vec![Check::ok(
+                &name,
+                format!("{} absent (optional)", path.display()),
+            )]
+        };
+    };
+
+    let mut value: serde_yaml::Value = match serde_yaml::from_str(&content) {
+        Ok(v) => v,
+        Err(e) => {
+            return vec![Check::fail(
+                &name,
+                format!("{}: YAML syntax error: {e}", path.display()),
+                "fix the YAML syntax \u{2014} until then this file is skipped",
+            )]
+        }
+    };
+    layer_checks(
+        &name,
+        &path.display().to_string(),
+        &config::validate::check_layer(&mut value),
+    )
+}
+
+/// `FORGE_REFLECT_*` overrides: value types and unknown keys.
+pub fn check_env(vars: impl IntoIterator<Item = (String, String)>) -> Vec<Check> {
+    let (mut layer, names) = config::env_layer(vars);
+    if names.is_empty() {
+        return Vec::new();
+    }
+    layer_checks(
+        "config env",
+        &names.join(", "),
+        &config::validate::check_layer(&mut layer),
+    )
+}
+
+/// One Ok check when `issues` is empty; else a Fail per dropped value and
+/// a Warn per unknown or retired key.
+fn layer_checks(name: &str, source: &str, issues: &[config::Issue]) -> Vec<Check> {
+    let mut checks: Vec<Check> = issues
+        .iter()
+        .map(|issue| match issue {
+            config::Issue::Unknown { key, suggestion } => Check::warn(
+                name,
+                issue.to_string(),
+                suggestion.as_ref().map_or_else(
+                    || format!("remove `{key}` from {source}"),
+                    |s| format!("rename `{key}` to `{s}` in {source}"),
+                ),
+            ),
+            config::Issue::Retired { key, read_as } => Check::warn(
+                name,
+                issue.to_string(),
+                format!("remove `{key}` from {source} (now read as {read_as})"),
+            ),
+            _ => Check::fail(
+                name,
+                format!("{source}: {issue}"),
+                format!("fix `{}` in {source}", issue.key()),
+            ),
+        })
+        .collect();
+    if checks.is_empty() {
+        checks.push(Check::ok(name, source));
+    }
+    checks
+}
+
+/// Values the loader reset because they were out of range.
+pub fn check_values(config: &Config) -> Vec<Check> {
+    config
+        .issues
+        .iter()
+        .filter(|i| matches!(i.issue, config::Issue::OutOfRange { .. }))
+        .map(|i| {
+            Check::warn(
+                "config values",
+                i.issue.to_string(),
+                format!("fix `{}` in the config layer that sets it", i.issue.key()),
+            )
+        })
+        .collect()
+}
+
+/// The effective layer stack, in merge order.
+pub fn check_layers(config: &Config) -> Check {
+    if config.layers.is_empty() && config.env_overrides.is_empty() {
+        return Check::warn(
+            "config layers",
+            "none \u{2014} compiled defaults in use",
+            "export FORGE_MODULE_ROOT=/path/to/forge-reflect",
+        );
+    }
+    let stack: Vec<String> = config
+        .layers
+        .iter()
+        .map(|p| p.display().to_string())
+        .chain(config.env_overrides.iter().map(|name| format!("${name}")))
+        .collect();
+    Check::ok("config layers", stack.join(" \u{2192} "))
+}
+
+/// Malformed `scope` rules, and whether the gates apply to `cwd`.
+pub fn check_scope(config: &Config, cwd: &str) -> Vec<Check> {
+    let mut checks = Vec::new();
+    for (index, rule) in config.scope.iter().enumerate() {
+        if rule.include.is_some() == rule.exclude.is_some() {
+            checks.push(Check::warn(
+                "scope",
+                format!(
+                    "rule {} sets neither or both of include/exclude and is skipped",
+                    index + 1
+                ),
+                "give each scope rule exactly one of `include:` or `exclude:`",
+            ));
+        }
+    }
+    let home = std::env::var("HOME").unwrap_or_default();
+    let scope = crate::scope::evaluate(&config.scope, cwd, &home);
+    let detail = format!("{cwd} \u{2014} {}", scope.describe());
+    checks.push(if scope.included {
+        Check::ok(
+            "scope",
+            format!(
+                "{detail} \u{2014} Stop gates apply, profile {} ({})",
+                config.profile.name(),
+                config.profile_source
+            ),
+        )
+    } else {
+        Check::ok("scope", format!("{detail} \u{2014} Stop gates skipped"))
+    });
+    checks
+}
+
+/// User root and vault paths: memory dirs, journal, backlog, archive.
+pub fn check_paths(config: &Config, cwd: &str) -> Vec<Check> {
+    let mut checks = Vec::new();
+    if config.user.root.is_empty() {
+        checks.push(Check::warn(
+            "user root",
+            format!("unset \u{2014} vault paths resolve against cwd '{cwd}'"),
+            "set `user: { root: <vault> }` in config.yaml",
+        ));
+    } else if Path::new(&config.user.root).is_dir() {
+        checks.push(Check::ok("user root", config.user.root.clone()));
+    } else {
+        checks.push(Check::fail(
+            "user root",
+            format!("{} does not exist", config.user.root),
+            "fix `user.root` in config.yaml",
+        ));
+    }
+    for root in &config.user.roots {
+        let key = format!("user root {}", root.name);
+        checks.push(if Path::new(&root.path).is_dir() {
+            Check::ok(&key, root.path.clone())
+        } else {
+            Check::fail(
+                &key,
+                format!("{} does not exist", root.path),
+                format!("fix the `{}` entry of `user.roots`", root.name),
+            )
+        });
+    }
+
+    let dirs = [
+        ("memory.insights", config.memory.insights.as_str()),
+        ("memory.imperatives", config.memory.imperatives.as_str()),
+        ("memory.ideas", config.memory.ideas.as_str()),
+        ("surface.archive_dir", config.surface.archive_dir.as_str()),
+    ];
+    for (key, relative) in dirs {
+        for path in config.resolve_user_paths(cwd, relative) {
+            checks.push(if path.is_dir() {
+                Check::ok(key, path.display().to_string())
+            } else {
+                Check::warn(
+                    key,
+                    format!("{} missing", path.display()),
+                    format!("mkdir -p '{}' or fix `{key}`", path.display()),
+                )
+            });
+        }
+    }
+
+    // Daily journal is a date template — check the directory before the first placeholder.
+    let journal_base = config
+        .journal
+        .daily
+        .split("YYYY")
+        .next()
+        .unwrap_or_default();
+    let journal = config.resolve_user_path(cwd, journal_base);
+    checks.push(if journal.is_dir() {
+        Check::ok("journal.daily", journal.display().to_string())
+    } else {
+        Check::warn(
+            "journal.daily",
+            format!("{} missing", journal.display()),
+            "create it or fix `journal.daily` in config.yaml",
+        )
+    });
+
+    let backlog = config.resolve_user_path(cwd, &config.backlog);
+    checks.push(if backlog.is_file() {
+        Check::ok("backlog", backlog.display().to_string())
+    } else {
+        Check::warn(
+            "backlog",
+            format!("{} missing", backlog.display()),
+            "create it or fix `backlog` in config.yaml \u{2014} surface rediscovery skips it",
+        )
+    });
+
+    checks
+}
+
+/// Skill files loaded as block reasons and prompts.
+pub fn check_skills(config: &Config, cwd: &str) -> Vec<Check> {
+    [
+        (
+            "reflection",
+            config.reflection.as_str(),
+            "reflect falls back to `fallback_reason`",
+        ),
+        (
+            "insight_check",
+            config.insight_check.as_str(),
+            "InsightCheck guidance is unavailable",
+        ),
+    ]
+    .into_iter()
+    .map(|(key, relative, consequence)| {
+        let path = config.resolve_user_path(cwd, relative);
+        if prompt::load_pattern_abs(&path).is_some() {
+            Check::ok(key, path.display().to_string())
+        } else {
+            Check::warn(
+                key,
+                format!(
+                    "{} unreadable or empty \u{2014} {consequence}",
+                    path.display()
+                ),
+                format!("install the skill or fix `{key}` in config.yaml"),
+            )
+        }
+    })
+    .collect()
+}
+
+/// Marker regexes compile, are non-empty and distinct.
+pub fn check_markers(config: &Config) -> Vec<Check> {
+    let markers = [
+        ("insight_marker", config.insight_marker.as_str()),
+        ("insight_skip_marker", config.insight_skip_marker.as_str()),
+        (
+            "insight_captured_marker",
+            config.insight_captured_marker.as_str(),
+        ),
+    ];
+    markers
+        .iter()
+        .map(|&(key, marker)| {
+            if marker.trim().is_empty() {
+                Check::fail(
+                    key,
+                    "empty marker matches every line",
+                    format!("set `{key}`"),
+                )
+            } else if markers
+                .iter()
+                .any(|&(other, m)| other != key && m == marker)
+            {
+                Check::fail(
+                    key,
+                    format!("'{marker}' is shared with another marker"),
+                    "give each marker a distinct prefix",
+                )
+            } else {
+                match transcript::marker_regex(marker) {
+                    Ok(_) => Check::ok(key, marker),
+                    Err(e) => Check::fail(
+                        key,
+                        format!("regex error: {e}"),
+                        format!("simplify `{key}`"),
+                    ),
+                }
+            }
+        })
+        .collect()
+}
+
+/// Hook registration: `hooks/hooks.json` parses and each `Stop`, `PreCompact`,
+/// `PostToolUse`, `SessionStart` and `UserPromptSubmit` command points at an
+/// existing script.
+pub fn check_hooks(module_root: &Path) -> Vec<Check> {
+    let path = module_root.join("hooks/hooks.json");
+    let Ok(content) = fs::read_to_string(&path) else {
+        return vec![Check::fail(
+            "hooks",
+            format!("{} missing", path.display()),
+            "restore hooks/hooks.json from the repository",
+        )];
+    };
+    let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) else {
+        return vec![Check::fail(
+            "hooks",
+            format!("{} is not valid JSON", path.display()),
+            "restore hooks/hooks.json from the repository",
+        )];
+    };
+
+    let root = module_root.to_string_lossy();
+    [
+        "Stop",
+        "PreCompact",
+        "PostToolUse",
+        "SessionStart",
+        "UserPromptSubmit",
+    ]
+    .into_iter()
+    .map(|event| {
+        let commands: Vec<&str> = json["hooks"][event]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .flat_map(|m| m["hooks"].as_array().into_iter().flatten())
+            .filter_map(|h| h["command"].as_str())
+            .collect();
+        let name = format!("hook {event}");
+        if commands.is_empty() {
+            return Check::fail(
+                &name,
+                "not registered",
+                format!("add a {event} entry to hooks/hooks.json"),
+            );
+        }
+        let missing: Vec<String> = commands
+            .iter()
+            .filter_map(|cmd| {
+                let script = cmd
+                    .split_whitespace()
+                    .last()?
+                    .replace("${CLAUDE_PLUGIN_ROOT}", &root);
+                (!Path::new(&script).is_file()).then_some(script)
+            })
+            .collect();
+        if missing.is_empty() {
+            Check::ok(&name, commands.join("; "))
+        } else {
+            Check::fail(
+                &name,
+                format!("script missing: {}", missing.join(", ")),
+                "reinstall the plugin or fix hooks/hooks.json",
+            )
+        }
+    })
+    .collect()
+}
+
+/// One line per check, with an indented fix line for warnings and failures.
+pub fn render_text(checks: &[Check]) -> String {
+    let mut out = String::new();
+    for c in checks {
+        let mark = match c.status {
+            Status::Ok => "ok  ",
+            Status::Warn => "WARN",
+            Status::Fail => "FAIL",
+        };
+        let _ = writeln!(out, "  {mark} {}: {}", c.name, c.detail);
+        if let Some(fix) = &c.fix {
+            let _ = writeln!(out, "       fix: {fix}");
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests;
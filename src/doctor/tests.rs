@@ -0,0 +1,171 @@
+use super::*;
+
+fn statuses(checks: &[Check]) -> Vec<Status> {
+    checks.iter().map(|c| c.status).collect()
+}
+
+#[test]
+fn test_config_file_syntax_error_fails() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("config.yaml");
+    fs::write(&path, "surface: [unclosed\n").unwrap();
+    let checks = check_config_file(&path, false);
+    assert_eq!(statuses(&checks), vec![Status::Fail]);
+    assert!(checks[0].detail.contains("YAML syntax error"));
+}
+
+#[test]
+fn test_config_file_type_error_and_unknown_key() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("config.yaml");
+    fs::write(&path, "tool_turn_threshold: many\ntool_turn_treshold: 5\n").unwrap();
+    let checks = check_config_file(&path, false);
+    assert_eq!(statuses(&checks), vec![Status::Fail, Status::Warn]);
+    assert!(checks[1].detail.contains("`tool_turn_treshold`"));
+}
+
+#[test]
+fn test_missing_optional_config_is_ok() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("config.yaml");
+    assert_eq!(statuses(&check_config_file(&path, false)), vec![Status::Ok]);
+    assert_eq!(
+        statuses(&check_config_file(&path, true)),
+        vec![Status::Fail]
+    );
+}
+
+#[test]
+fn test_missing_user_root_fails() {
+    let mut config = Config::default();
+    config.user.root = "/nonexistent/forge-reflect-vault".to_string();
+    let checks = check_paths(&config, "/tmp");
+    assert_eq!(checks[0].name, "user root");
+    assert_eq!(checks[0].status, Status::Fail);
+}
+
+#[test]
+fn test_existing_paths_ok() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut config = Config::default();
+    config.user.root = dir.path().to_string_lossy().into_owned();
+    fs::create_dir_all(dir.path().join(&config.memory.insights)).unwrap();
+    let checks = check_paths(&config, "/tmp");
+    let insights = checks.iter().find(|c| c.name == "memory.insights").unwrap();
+    assert_eq!(insights.status, Status::Ok);
+    let ideas = checks.iter().find(|c| c.name == "memory.ideas").unwrap();
+    assert_eq!(ideas.status, Status::Warn);
+    assert!(ideas.fix.as_deref().unwrap().starts_with("mkdir -p"));
+}
+
+#[test]
+fn test_duplicate_and_empty_markers_fail() {
+    let config = Config {
+        insight_skip_marker: "\u{2605} Insight".to_string(),
+        insight_captured_marker: " ".to_string(),
+        ..Config::default()
+    };
+    assert_eq!(
+        statuses(&check_markers(&config)),
+        vec![Status::Fail, Status::Fail, Status::Fail]
+    );
+    assert_eq!(
+        statuses(&check_markers(&Config::default())),
+        vec![Status::Ok, Status::Ok, Status::Ok]
+    );
+}
+
+#[test]
+fn test_hooks_registration() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("hooks")).unwrap();
+    fs::write(dir.path().join("hooks/Stop.sh"), "").unwrap();
+    fs::write(
+        dir.path().join("hooks/hooks.json"),
+        r#"{"hooks":{"Stop":[{"matcher":"","hooks":[{"type":"command","command":"bash ${CLAUDE_PLUGIN_ROOT}/hooks/Stop.sh"}]}],
+            "PreCompact":[{"matcher":"","hooks":[{"type":"command","command":"bash ${CLAUDE_PLUGIN_ROOT}/hooks/PreCompact.sh"}]}]}}"#,
+    )
+    .unwrap();
+    let checks = check_hooks(dir.path());
+    assert_eq!(
+        statuses(&checks),
+        vec![
+            Status::Ok,
+            Status::Fail,
+            Status::Fail,
+            Status::Fail,
+            Status::Fail
+        ]
+    );
+    assert!(checks[1].detail.contains("script missing"));
+    assert_eq!(checks[2].detail, "not registered");
+    assert_eq!(checks[3].name, "hook SessionStart");
+}
+
+#[test]
+fn test_render_text_includes_fix() {
+    let checks = vec![
+        Check::ok("module root", "/m"),
+        Check::warn("backlog", "/v/Backlog.md missing", "fix `backlog`"),
+    ];
+    let text = render_text(&checks);
+    assert!(text.contains("  ok   module root: /m"));
+    assert!(text.contains("  WARN backlog: /v/Backlog.md missing\n       fix: fix `backlog`"));
+}
+
+#[test]
+fn test_layer_stack_in_merge_order() {
+    let mut config = Config::default();
+    assert_eq!(check_layers(&config).status, Status::Warn);
+    config.layers = vec![
+        "/m/defaults.yaml".into(),
+        "/repo/.forge-reflect.yaml".into(),
+    ];
+    let check = check_layers(&config);
+    assert_eq!(check.status, Status::Ok);
+    assert_eq!(
+        check.detail,
+        "/m/defaults.yaml \u{2192} /repo/.forge-reflect.yaml"
+    );
+}
+
+#[test]
+fn test_scope_reports_match_and_malformed_rules() {
+    let config = Config {
+        scope: vec![
+            config::ScopeRule::default(),
+            config::ScopeRule {
+                include: Some("/work/**".to_string()),
+                ..config::ScopeRule::default()
+            },
+        ],
+        ..Config::default()
+    };
+    let checks = check_scope(&config, "/work/repo");
+    assert_eq!(statuses(&checks), vec![Status::Warn, Status::Ok]);
+    assert!(checks[1].detail.contains("rule 2: include /work/**"));
+    assert!(checks[1]
+        .detail
+        .ends_with("Stop gates apply, profile strict (config)"));
+}
+
+#[test]
+fn test_env_override_type_error_and_unknown_key() {
+    let vars = |pairs: &[(&str, &str)]| {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect::<Vec<_>>()
+    };
+    assert!(check_env(vars(&[("PATH", "/bin")])).is_empty());
+    let checks = check_env(vars(&[
+        ("FORGE_REFLECT_SURFACE__MAX_ITEMS", "many"),
+        ("FORGE_REFLECT_SURFACE__MAX_ITEM", "3"),
+    ]));
+    assert_eq!(statuses(&checks), vec![Status::Fail, Status::Warn]);
+    assert!(checks[1]
+        .fix
+        .as_deref()
+        .unwrap()
+        .contains("FORGE_REFLECT_SURFACE__MAX_ITEM"));
+}
@@ -0,0 +1,158 @@
+//! Decision trace for `--explain` — records what a gate saw and why it decided.
+//!
+//! Handlers append steps as they go; the CLI renders the trace to stderr so
+//! the stdout hook contract is untouched.
+
+use crate::config::Config;
+use crate::gate::{self, TopicStatus};
+use crate::transcript::TranscriptAnalysis;
+use serde::Serialize;
+use std::fmt::Write;
+
+#[derive(Debug, Default, Serialize)]
+pub struct Trace {
+    /// Gate name, e.g. `insight` or `reflect`.
+    pub gate: String,
+    /// Module root and the source that found it. Empty = not found.
+    pub module_root: String,
+    /// Config files merged, in order. Empty = compiled defaults.
+    pub config_layers: Vec<String>,
+    /// Enforcement profile in effect and where it came from.
+    pub profile: String,
+    /// Ordered checks: guards, scope and thresholds.
+    pub steps: Vec<Step>,
+    /// Named insight topics with their match evidence.
+    pub topics: Vec<Topic>,
+    /// Memory writes and what they were credited for.
+    pub writes: Vec<Credit>,
+    /// Final decision, e.g. `allow — not substantial`.
+    pub decision: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Step {
+    pub check: String,
+    pub detail: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Topic {
+    pub topic: String,
+    #[serde(flatten)]
+    pub status: TopicStatus,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Credit {
+    pub path: String,
+    pub memory_path: String,
+    pub topics: Vec<String>,
+}
+
+impl Trace {
+    pub fn new(gate: &str, config: &Config) -> Self {
+        Self {
+            gate: gate.to_string(),
+            module_root: config
+                .module_root
+                .as_ref()
+                .map(|(root, source)| format!("{} (from {source})", root.display()))
+                .unwrap_or_default(),
+            config_layers: config
+                .layers
+                .iter()
+                .map(|p| p.display().to_string())
+                .chain(config.env_overrides.iter().map(|name| format!("${name}")))
+                .collect(),
+            profile: format!("{} ({})", config.profile.name(), config.profile_source),
+            ..Self::default()
+        }
+    }
+
+    pub fn step(&mut self, check: &str, detail: impl Into<String>) {
+        self.steps.push(Step {
+            check: check.to_string(),
+            detail: detail.into(),
+        });
+    }
+
+    /// Record a `actual >= required` threshold comparison. Returns whether it passed.
+    pub fn threshold(&mut self, name: &str, actual: u64, required: u64) -> bool {
+        let passed = actual >= required;
+        let mark = if passed { "\u{2713}" } else { "\u{2717}" };
+        self.step("threshold", format!("{name} {actual} >= {required} {mark}"));
+        passed
+    }
+
+    /// Record topic evidence and credited writes from a transcript analysis.
+    pub fn record_analysis(&mut self, analysis: &TranscriptAnalysis, config: &Config) {
+        self.topics = gate::topic_evidence(analysis)
+            .into_iter()
+            .map(|e| Topic {
+                topic: e.topic.to_string(),
+                status: e.status,
+            })
+            .collect();
+        self.writes = gate::credited_writes(analysis, config)
+            .into_iter()
+            .map(|w| Credit {
+                path: w.path.to_string(),
+                memory_path: w.memory_path,
+                topics: w.topics.iter().map(ToString::to_string).collect(),
+            })
+            .collect();
+    }
+
+    pub fn decide(&mut self, decision: impl Into<String>) {
+        self.decision = Some(decision.into());
+    }
+
+    /// Human-readable multi-line rendering.
+    pub fn render_text(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "forge-reflect[{}] explain", self.gate);
+        if self.module_root.is_empty() {
+            let _ = writeln!(out, "  module:    not found");
+        } else {
+            let _ = writeln!(out, "  module:    {}", self.module_root);
+        }
+        if self.config_layers.is_empty() {
+            let _ = writeln!(out, "  config:    compiled defaults");
+        } else {
+            for layer in &self.config_layers {
+                let _ = writeln!(out, "  config:    {layer}");
+            }
+        }
+        let _ = writeln!(out, "  profile:   {}", self.profile);
+        for step in &self.steps {
+            let _ = writeln!(out, "  {:<10} {}", format!("{}:", step.check), step.detail);
+        }
+        for topic in &self.topics {
+            let evidence = match &topic.status {
+                TopicStatus::Written(file) => format!("captured by write {file}"),
+                TopicStatus::Skipped(skip) => format!("skipped via \u{2606} marker \"{skip}\""),
+                TopicStatus::Marked(mark) => format!("captured via \u{2713} marker \"{mark}\""),
+                TopicStatus::Uncaptured => "UNCAPTURED".to_string(),
+            };
+            let _ = writeln!(out, "  topic:     \"{}\" \u{2014} {evidence}", topic.topic);
+        }
+        for write in &self.writes {
+            let credit = if write.topics.is_empty() {
+                String::new()
+            } else {
+                format!(", captures {}", write.topics.join(", "))
+            };
+            let _ = writeln!(
+                out,
+                "  write:     {} (matches {}{credit})",
+                write.path, write.memory_path
+            );
+        }
+        let decision = self.decision.as_deref().unwrap_or("none");
+        let _ = writeln!(out, "  decision:  {decision}");
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests;
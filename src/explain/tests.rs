@@ -0,0 +1,74 @@
+use super::*;
+
+fn analysis() -> TranscriptAnalysis {
+    TranscriptAnalysis {
+        insight_count: 2,
+        insight_topics: vec![
+            "Config Merge Order".to_string(),
+            "Regex Anchoring Rules".to_string(),
+        ],
+        insights_write_count: 1,
+        insights_written: vec!["Config Merge Order.md".to_string()],
+        has_memory_write: true,
+        memory_writes: vec!["/vault/Memory/Insights/Config Merge Order.md".to_string()],
+        ..TranscriptAnalysis::default()
+    }
+}
+
+#[test]
+fn test_threshold_records_pass_and_fail() {
+    let mut trace = Trace::default();
+    assert!(trace.threshold("user messages", 4, 4));
+    assert!(!trace.threshold("tool turns", 3, 10));
+    assert_eq!(trace.steps.len(), 2);
+    assert!(trace.steps[1].detail.contains("3 >= 10"));
+}
+
+#[test]
+fn test_record_analysis_topics_and_writes() {
+    let config = Config::default();
+    let mut trace = Trace::new("insight", &config);
+    trace.record_analysis(&analysis(), &config);
+
+    assert_eq!(
+        trace.topics[0].status,
+        TopicStatus::Written("Config Merge Order.md".to_string())
+    );
+    assert_eq!(trace.topics[1].status, TopicStatus::Uncaptured);
+    assert_eq!(trace.writes.len(), 1);
+    assert_eq!(trace.writes[0].memory_path, "Memory/Insights/");
+    assert_eq!(trace.writes[0].topics, vec!["Config Merge Order"]);
+}
+
+#[test]
+fn test_render_text_lists_everything() {
+    let config = Config::default();
+    let mut trace = Trace::new("insight", &config);
+    trace.step("scope", "cwd inside data dir");
+    trace.record_analysis(&analysis(), &config);
+    trace.decide("block \u{2014} 1 uncaptured");
+
+    let text = trace.render_text();
+    assert!(text.contains("forge-reflect[insight] explain"));
+    assert!(text.contains("module:    not found"));
+    assert!(text.contains("config:    compiled defaults"));
+    assert!(text.contains("profile:   strict (config)"));
+    assert!(text.contains("scope:     cwd inside data dir"));
+    assert!(text.contains("\"Regex Anchoring Rules\" \u{2014} UNCAPTURED"));
+    assert!(text.contains("captures Config Merge Order"));
+    assert!(text.contains("decision:  block"));
+}
+
+#[test]
+fn test_json_shape() {
+    let mut trace = Trace::new("reflect", &Config::default());
+    trace.topics.push(Topic {
+        topic: "Config Merge Order".to_string(),
+        status: TopicStatus::Skipped("config merge order".to_string()),
+    });
+    let json = serde_json::to_value(&trace).unwrap();
+    assert_eq!(json["gate"], "reflect");
+    assert_eq!(json["topics"][0]["status"], "skipped");
+    assert_eq!(json["topics"][0]["evidence"], "config merge order");
+    assert!(json["decision"].is_null());
+}
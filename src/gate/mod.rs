@@ -0,0 +1,272 @@
+//! Gate decisions — pure functions over a [`TranscriptAnalysis`].
+//!
+//! No I/O — the CLI layer reads transcripts and emits hook output.
+
+use crate::capture::{self, Kind, Request};
+use crate::config::{Config, StopGate};
+use crate::transcript::{self, TranscriptAnalysis};
+use serde::Serialize;
+use std::path::Path;
+
+/// Insights announced in the transcript that were never persisted.
+#[derive(Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Uncaptured<'a> {
+    /// Named topics with no matching file write, ☆ skip marker or ✓ captured marker.
+    pub topics: Vec<&'a str>,
+    /// Unnamed insight blocks not covered by surplus insight file writes.
+    pub unnamed: usize,
+}
+
+impl Uncaptured<'_> {
+    pub fn total(&self) -> usize {
+        self.topics.len() + self.unnamed
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total() == 0
+    }
+
+    /// Reason suffix: `": a, b"` for named topics, `" (2 unnamed)"` otherwise.
+    pub fn detail(&self) -> String {
+        if !self.topics.is_empty() {
+            format!(": {}", self.topics.join(", "))
+        } else if self.unnamed > 0 {
+            format!(" ({} unnamed)", self.unnamed)
+        } else {
+            String::new()
+        }
+    }
+
+    /// Topic list for prompts: named topics, or `"2 unnamed"` when there are none.
+    pub fn topics_display(&self) -> String {
+        if self.topics.is_empty() {
+            format!("{} unnamed", self.unnamed)
+        } else {
+            self.topics.join(", ")
+        }
+    }
+}
+
+/// How a named insight topic was (or was not) accounted for.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", content = "evidence", rename_all = "snake_case")]
+pub enum TopicStatus {
+    /// A file written to the insights path shares a token with the topic.
+    Written(String),
+    /// A ☆ Insight skip marker names the topic.
+    Skipped(String),
+    /// A ✓ Insight captured marker names the topic.
+    Marked(String),
+    Uncaptured,
+}
+
+/// A named insight topic with the evidence that settled it.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct TopicEvidence<'a> {
+    pub topic: &'a str,
+    #[serde(flatten)]
+    pub status: TopicStatus,
+}
+
+/// A memory write and what it was credited for.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct WriteCredit<'a> {
+    pub path: &'a str,
+    /// The `memory_paths` fragment or vault memory directory the path matched.
+    pub memory_path: String,
+    /// Insight topics this write captures (insights path only).
+    pub topics: Vec<&'a str>,
+}
+
+/// Match each named topic against written filenames, then ☆ skip markers,
+/// then ✓ captured markers. First match wins.
+pub fn topic_evidence(analysis: &TranscriptAnalysis) -> Vec<TopicEvidence<'_>> {
+    analysis
+        .insight_topics
+        .iter()
+        .map(|topic| {
+            let topic_lower = topic.to_lowercase();
+            let written = analysis.insights_written.iter().find(|written| {
+                let written_base = written
+                    .strip_suffix(".md")
+                    .unwrap_or(written)
+                    .to_lowercase();
+                transcript::topic_matches_filename(&topic_lower, &written_base)
+            });
+            let matches = |m: &&String| transcript::topic_matches_filename(&topic_lower, m);
+            let status = if let Some(file) = written {
+                TopicStatus::Written(file.clone())
+            } else if let Some(skip) = analysis.skipped_topics.iter().find(matches) {
+                TopicStatus::Skipped(skip.clone())
+            } else if let Some(mark) = analysis.captured_topics.iter().find(matches) {
+                TopicStatus::Marked(mark.clone())
+            } else {
+                TopicStatus::Uncaptured
+            };
+            TopicEvidence {
+                topic: topic.as_str(),
+                status,
+            }
+        })
+        .collect()
+}
+
+/// Compute uncaptured insights: named topics without evidence (see
+/// [`topic_evidence`]); unnamed blocks are offset by surplus writes.
+pub fn uncaptured_insights(analysis: &TranscriptAnalysis) -> Uncaptured<'_> {
+    let topics: Vec<&str> = topic_evidence(analysis)
+        .into_iter()
+        .filter(|e| e.status == TopicStatus::Uncaptured)
+        .map(|e| e.topic)
+        .collect();
+
+    let unnamed_insights = analysis
+        .insight_count
+        .saturating_sub(analysis.insight_topics.len());
+    let named_matched = analysis.insight_topics.len().saturating_sub(topics.len());
+    let surplus_writes = analysis.insights_write_count.saturating_sub(named_matched);
+    let unnamed = unnamed_insights.saturating_sub(surplus_writes);
+
+    Uncaptured { topics, unnamed }
+}
+
+/// Explain each memory write: which `memory_paths` fragment it matched and,
+/// for insight files, which topics it captured.
+pub fn credited_writes<'a>(
+    analysis: &'a TranscriptAnalysis,
+    config: &'a Config,
+) -> Vec<WriteCredit<'a>> {
+    analysis
+        .memory_writes
+        .iter()
+        .filter_map(|path| {
+            let (memory_path, insight) = config.memory_write(path)?;
+            let topics = if insight {
+                let base = std::path::Path::new(path)
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().to_lowercase())
+                    .unwrap_or_default();
+                analysis
+                    .insight_topics
+                    .iter()
+                    .filter(|t| transcript::topic_matches_filename(&t.to_lowercase(), &base))
+                    .map(String::as_str)
+                    .collect()
+            } else {
+                Vec::new()
+            };
+            Some(WriteCredit {
+                path,
+                memory_path,
+                topics,
+            })
+        })
+        .collect()
+}
+
+/// Insight topics that were mentioned but not written to files.
+/// Unlike [`uncaptured_insights`], ignores ☆/✓ markers — used for the
+/// `PreCompact` topic list, where over-reporting is harmless.
+pub fn unwritten_topics(analysis: &TranscriptAnalysis) -> Vec<&str> {
+    analysis
+        .insight_topics
+        .iter()
+        .filter_map(|topic| {
+            let topic_lower = topic.to_lowercase();
+            let matched = analysis.insights_written.iter().any(|written| {
+                let base = written
+                    .strip_suffix(".md")
+                    .unwrap_or(written)
+                    .to_lowercase();
+                transcript::topic_matches_filename(&topic_lower, &base)
+            });
+            if matched {
+                None
+            } else {
+                Some(topic.as_str())
+            }
+        })
+        .collect()
+}
+
+/// Insight gate substantiality: enough user messages AND tool-using turns.
+pub fn insight_substantial(analysis: &TranscriptAnalysis, config: &Config) -> bool {
+    analysis.user_messages >= config.user_msg_threshold
+        && analysis.tool_using_turns >= config.tool_turn_threshold
+}
+
+/// Reflect gate substantiality: long enough AND above the user message floor.
+pub fn reflect_substantial(analysis: &TranscriptAnalysis, config: &Config) -> bool {
+    analysis.session_duration_minutes >= u64::from(config.duration_threshold_minutes)
+        && analysis.user_messages >= config.user_msg_floor
+}
+
+/// Would the insight Stop gate block? Ignores scope and hook guards.
+pub fn insight_blocks(analysis: &TranscriptAnalysis, config: &Config) -> bool {
+    config.profile.blocks()
+        && insight_substantial(analysis, config)
+        && !uncaptured_insights(analysis).is_empty()
+}
+
+/// Would the reflect Stop gate block? Ignores scope and hook guards.
+pub fn reflect_blocks(analysis: &TranscriptAnalysis, config: &Config) -> bool {
+    config.profile.blocks() && reflect_substantial(analysis, config) && !analysis.has_memory_write
+}
+
+/// Paths among `writes` inside `kind`'s memory directory in any vault.
+/// Relative paths resolve against `cwd`.
+pub fn capture_writes(kind: Kind, config: &Config, cwd: &str, writes: &[String]) -> usize {
+    let dirs = config.resolve_user_paths(cwd, kind.dir(&config.memory));
+    writes
+        .iter()
+        .filter(|path| {
+            let path = Path::new(cwd).join(path);
+            dirs.iter().any(|dir| path.starts_with(dir))
+        })
+        .count()
+}
+
+/// Capture requests with no later write in their kind's memory directory.
+pub fn pending_captures<'a>(
+    requests: &'a [Request],
+    analysis: &TranscriptAnalysis,
+    config: &Config,
+    cwd: &str,
+) -> Vec<&'a Request> {
+    capture::pending(requests, |kind| {
+        capture_writes(kind, config, cwd, &analysis.file_writes)
+    })
+}
+
+/// Would the capture Stop gate block? Ignores scope and hook guards.
+pub fn capture_blocks(
+    requests: &[Request],
+    analysis: &TranscriptAnalysis,
+    config: &Config,
+    cwd: &str,
+) -> bool {
+    config.profile.blocks() && !pending_captures(requests, analysis, config, cwd).is_empty()
+}
+
+/// Gates in `stop_gates` order that would block. Ignores scope, hook guards,
+/// override phrases and `block_budget` — the Stop hook applies those after.
+pub fn stop_blocks(
+    analysis: &TranscriptAnalysis,
+    config: &Config,
+    requests: &[Request],
+    cwd: &str,
+) -> Vec<StopGate> {
+    config
+        .stop_gates
+        .iter()
+        .copied()
+        .filter(|gate| match gate {
+            StopGate::Insight => insight_blocks(analysis, config),
+            StopGate::Reflect => reflect_blocks(analysis, config),
+            StopGate::Capture => capture_blocks(requests, analysis, config, cwd),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,111 @@
+use super::*;
+
+fn analysis(topics: &[&str], written: &[&str]) -> TranscriptAnalysis {
+    TranscriptAnalysis {
+        insight_count: topics.len(),
+        insight_topics: topics.iter().map(ToString::to_string).collect(),
+        insights_write_count: written.len(),
+        insights_written: written.iter().map(ToString::to_string).collect(),
+        ..TranscriptAnalysis::default()
+    }
+}
+
+// ─── uncaptured_insights ───
+
+#[test]
+fn test_matching_write_captures_topic() {
+    let a = analysis(&["Config Merge Order"], &["Config Merge Order.md"]);
+    assert!(uncaptured_insights(&a).is_empty());
+}
+
+#[test]
+fn test_unmatched_topic_is_uncaptured() {
+    let a = analysis(&["Config Merge Order"], &["Unrelated Note.md"]);
+    let uncaptured = uncaptured_insights(&a);
+    assert_eq!(uncaptured.topics, vec!["Config Merge Order"]);
+    assert_eq!(uncaptured.detail(), ": Config Merge Order");
+}
+
+#[test]
+fn test_skip_and_captured_markers_clear_topics() {
+    let mut a = analysis(&["Config Merge Order", "Regex Anchoring Rules"], &[]);
+    a.skipped_topics.push("config merge order".to_string());
+    a.captured_topics.push("regex anchoring rules".to_string());
+    assert!(uncaptured_insights(&a).is_empty());
+}
+
+#[test]
+fn test_unnamed_offset_by_surplus_writes() {
+    let mut a = analysis(&[], &["Something.md"]);
+    a.insight_count = 2;
+    let uncaptured = uncaptured_insights(&a);
+    assert_eq!(uncaptured.unnamed, 1);
+    assert_eq!(uncaptured.total(), 1);
+    assert_eq!(uncaptured.detail(), " (1 unnamed)");
+    assert_eq!(uncaptured.topics_display(), "1 unnamed");
+}
+
+// ─── unwritten_topics ───
+
+#[test]
+fn test_unwritten_topics_ignores_markers() {
+    let mut a = analysis(&["Config Merge Order"], &[]);
+    a.skipped_topics.push("config merge order".to_string());
+    assert_eq!(unwritten_topics(&a), vec!["Config Merge Order"]);
+}
+
+// ─── substantiality ───
+
+#[test]
+fn test_insight_substantial_needs_both_thresholds() {
+    let config = Config::default();
+    let mut a = TranscriptAnalysis {
+        user_messages: config.user_msg_threshold,
+        tool_using_turns: config.tool_turn_threshold - 1,
+        ..TranscriptAnalysis::default()
+    };
+    assert!(!insight_substantial(&a, &config));
+    a.tool_using_turns += 1;
+    assert!(insight_substantial(&a, &config));
+}
+
+#[test]
+fn test_reflect_substantial_needs_duration_and_floor() {
+    let config = Config::default();
+    let mut a = TranscriptAnalysis {
+        user_messages: config.user_msg_floor,
+        session_duration_minutes: u64::from(config.duration_threshold_minutes) - 1,
+        ..TranscriptAnalysis::default()
+    };
+    assert!(!reflect_substantial(&a, &config));
+    a.session_duration_minutes += 1;
+    assert!(reflect_substantial(&a, &config));
+}
+
+// ─── blocking verdicts ───
+
+#[test]
+fn test_insight_blocks_respects_profile() {
+    let mut config = Config::default();
+    let mut a = analysis(&["Config Merge Order"], &[]);
+    a.user_messages = config.user_msg_threshold;
+    a.tool_using_turns = config.tool_turn_threshold;
+    assert!(insight_blocks(&a, &config));
+    config.profile = crate::config::Profile::AuditOnly;
+    assert!(insight_blocks(&a, &config));
+    config.profile = crate::config::Profile::Advisory;
+    assert!(!insight_blocks(&a, &config));
+}
+
+#[test]
+fn test_reflect_blocks_only_without_memory_write() {
+    let config = Config::default();
+    let mut a = TranscriptAnalysis {
+        user_messages: config.user_msg_floor,
+        session_duration_minutes: u64::from(config.duration_threshold_minutes),
+        ..TranscriptAnalysis::default()
+    };
+    assert!(reflect_blocks(&a, &config));
+    a.has_memory_write = true;
+    assert!(!reflect_blocks(&a, &config));
+}
@@ -0,0 +1,65 @@
+//! Hook registration generators for hosts without a checked-in config.
+//!
+//! Claude Code reads `hooks/hooks.json` from the plugin; Gemini CLI needs a
+//! `hooks` block merged into `~/.gemini/settings.json` (or the project's
+//! `.gemini/settings.json`); Codex CLI runs a single `notify` program named
+//! in `~/.codex/config.toml`.
+
+use serde_json::{json, Value};
+
+/// Gemini CLI `settings.json` hooks block running `bin` (absolute path to
+/// `forge-reflect`) for each lifecycle event forge-reflect handles.
+pub fn gemini_settings(bin: &str) -> Value {
+    let bin = shell_quote(bin);
+    let hook = |name: &str, args: &str, description: &str| {
+        json!({
+            "name": format!("forge-reflect-{name}"),
+            "type": "command",
+            "command": format!("{bin} {args} --host gemini"),
+            "description": description
+        })
+    };
+    json!({
+        "hooks": {
+            "SessionStart": [{ "hooks": [
+                hook("surface", "surface --format json", "Surface digest: stale ideas and rediscovery pool")
+            ]}],
+            "BeforeAgent": [{ "hooks": [
+                hook("userpromptsubmit", "userpromptsubmit", "Capture requests in \"remember that ...\" prompts")
+            ]}],
+            "AfterTool": [{ "matcher": "activate_skill", "hooks": [
+                hook("posttooluse", "posttooluse", "Journals surface digest and advisory insight checks")
+            ]}],
+            "AfterAgent": [{ "hooks": [
+                hook("stop", "stop", "Insight and reflect gates before the session ends")
+            ]}],
+            "PreCompress": [{ "hooks": [
+                hook("precompact", "precompact", "Reflection prompt before context compression")
+            ]}]
+        }
+    })
+}
+
+/// `word` as one POSIX shell word: unchanged when it is plain, else single
+/// quoted.
+fn shell_quote(word: &str) -> String {
+    let plain = !word.is_empty()
+        && word
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"/._-+:@%=,".contains(&b));
+    if plain {
+        word.to_string()
+    } else {
+        format!("'{}'", word.replace('\'', r"'\''"))
+    }
+}
+
+/// Codex CLI `config.toml` line running `forge-reflect notify` after each
+/// agent turn. Codex appends the JSON payload as the last argument.
+pub fn codex_notify(bin: &str) -> String {
+    let argv = serde_json::to_string(&[bin, "notify"]).unwrap_or_default();
+    format!("notify = {argv}")
+}
+
+#[cfg(test)]
+mod tests;
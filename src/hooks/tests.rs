@@ -0,0 +1,40 @@
+use super::*;
+
+#[test]
+fn test_gemini_settings_registers_each_event() {
+    let settings = gemini_settings("/opt/forge-reflect");
+    for (event, subcommand) in [
+        ("SessionStart", "surface"),
+        ("BeforeAgent", "userpromptsubmit"),
+        ("AfterTool", "posttooluse"),
+        ("AfterAgent", "stop"),
+        ("PreCompress", "precompact"),
+    ] {
+        let command = settings["hooks"][event][0]["hooks"][0]["command"]
+            .as_str()
+            .unwrap();
+        assert!(command.starts_with(&format!("/opt/forge-reflect {subcommand}")));
+        assert!(command.ends_with("--host gemini"));
+    }
+    assert_eq!(
+        settings["hooks"]["AfterTool"][0]["matcher"],
+        "activate_skill"
+    );
+}
+
+#[test]
+fn test_gemini_command_quotes_the_binary_path() {
+    let settings = gemini_settings("/Users/a b/it's/forge-reflect");
+    assert_eq!(
+        settings["hooks"]["AfterAgent"][0]["hooks"][0]["command"],
+        r"'/Users/a b/it'\''s/forge-reflect' stop --host gemini"
+    );
+}
+
+#[test]
+fn test_codex_notify_is_a_toml_argv() {
+    assert_eq!(
+        codex_notify("/opt/forge-reflect"),
+        r#"notify = ["/opt/forge-reflect","notify"]"#
+    );
+}
@@ -1,3 +1,5 @@
+pub mod template;
+
 use std::fs;
 use std::path::Path;
 
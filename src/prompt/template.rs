@@ -0,0 +1,146 @@
+//! Message templates for hook strings. `{name}` is replaced by a variable;
+//! `{?name}…{/name}` renders only when `name` is set (non-empty and not
+//! `0`), `{!name}…{/name}` only when it is not. Any other brace is literal.
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+/// Variables every message can use. Unknown at the time of a message (no
+/// transcript yet, say), they are unset.
+pub const SESSION: [&str; 6] = [
+    "duration",
+    "user_messages",
+    "tool_turns",
+    "count",
+    "topics",
+    "insights_dir",
+];
+
+/// Values for one rendering.
+#[derive(Debug, Default, Clone)]
+pub struct Vars(BTreeMap<&'static str, String>);
+
+impl Vars {
+    /// Set `name`, replacing any earlier value.
+    #[must_use]
+    pub fn with(mut self, name: &'static str, value: impl Display) -> Self {
+        self.0.insert(name, value.to_string());
+        self
+    }
+
+    /// The value of `name`; empty when unset.
+    pub fn get(&self, name: &str) -> &str {
+        self.0.get(name).map_or("", String::as_str)
+    }
+
+    /// `name` has a value other than empty or `0`.
+    pub fn is_set(&self, name: &str) -> bool {
+        !matches!(self.get(name), "" | "0")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Piece<'a> {
+    Text(&'a str),
+    Var(&'a str),
+    /// Section start; `true` for `{!name}`.
+    Open(&'a str, bool),
+    Close(&'a str),
+}
+
+/// Expand `template`. Unset variables render empty; a stray `{/name}` is
+/// ignored and an unclosed section runs to the end.
+pub fn render(template: &str, vars: &Vars) -> String {
+    let mut out = String::new();
+    let mut sections: Vec<(&str, bool)> = Vec::new();
+    for piece in pieces(template) {
+        let shown = sections.iter().all(|(_, shown)| *shown);
+        match piece {
+            Piece::Text(text) if shown => out.push_str(text),
+            Piece::Var(name) if shown => out.push_str(vars.get(name)),
+            Piece::Text(_) | Piece::Var(_) => {}
+            Piece::Open(name, negated) => sections.push((name, vars.is_set(name) != negated)),
+            Piece::Close(name) => {
+                if sections.last().is_some_and(|(open, _)| *open == name) {
+                    sections.pop();
+                }
+            }
+        }
+    }
+    out
+}
+
+/// First problem with `template`: a placeholder outside `known`, or a
+/// section that is not closed in order.
+pub fn check(template: &str, known: &[&str]) -> Result<(), String> {
+    let mut sections = Vec::new();
+    for piece in pieces(template) {
+        match piece {
+            Piece::Var(name) | Piece::Open(name, _) if !known.contains(&name) => {
+                return Err(format!(
+                    "uses unknown placeholder `{{{name}}}` (known: {})",
+                    known.join(", ")
+                ));
+            }
+            Piece::Text(_) | Piece::Var(_) => {}
+            Piece::Open(name, _) => sections.push(name),
+            Piece::Close(name) => {
+                if sections.pop() != Some(name) {
+                    return Err(format!("closes section `{name}` that is not open"));
+                }
+            }
+        }
+    }
+    match sections.pop() {
+        Some(name) => Err(format!("leaves section `{name}` open")),
+        None => Ok(()),
+    }
+}
+
+fn pieces(template: &str) -> Vec<Piece<'_>> {
+    let mut pieces = Vec::new();
+    let mut rest = template;
+    let mut text_start = 0;
+    let mut offset = 0;
+    while let Some(at) = rest.find('{') {
+        let start = offset + at;
+        match tag(&template[start..]) {
+            Some((piece, len)) => {
+                if text_start < start {
+                    pieces.push(Piece::Text(&template[text_start..start]));
+                }
+                pieces.push(piece);
+                text_start = start + len;
+                offset = text_start;
+            }
+            None => offset = start + 1,
+        }
+        rest = &template[offset..];
+    }
+    if text_start < template.len() {
+        pieces.push(Piece::Text(&template[text_start..]));
+    }
+    pieces
+}
+
+/// The tag at the start of `s` (which begins with `{`) and its length.
+fn tag(s: &str) -> Option<(Piece<'_>, usize)> {
+    let end = s.find('}')?;
+    let inner = &s[1..end];
+    let name = inner.trim_start_matches(['?', '!', '/']);
+    let valid = inner.len() - name.len() <= 1
+        && !name.is_empty()
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_');
+    if !valid {
+        return None;
+    }
+    let piece = match inner.as_bytes()[0] {
+        b'?' => Piece::Open(name, false),
+        b'!' => Piece::Open(name, true),
+        b'/' => Piece::Close(name),
+        _ => Piece::Var(name),
+    };
+    Some((piece, end + 1))
+}
@@ -20,3 +20,51 @@ fn test_no_frontmatter_no_h1() {
     let result = strip_frontmatter_and_h1(input);
     assert_eq!(result.trim(), "Just body text.");
 }
+
+#[test]
+fn test_template_renders_variables_and_sections() {
+    let vars = template::Vars::default()
+        .with("count", 2)
+        .with("topics", "a, b")
+        .with("drafts", "");
+    let text = "{count} uncaptured{?topics}: {topics}{/topics}.{?drafts} See {drafts}.{/drafts}{!drafts} No drafts.{/drafts}";
+    assert_eq!(
+        template::render(text, &vars),
+        "2 uncaptured: a, b. No drafts."
+    );
+    let none = template::Vars::default().with("count", 0);
+    assert_eq!(
+        template::render(
+            "{?count}{count} left{/count}{!count}all done{/count}",
+            &none
+        ),
+        "all done"
+    );
+}
+
+#[test]
+fn test_template_leaves_other_braces_literal() {
+    let vars = template::Vars::default().with("kind", "idea");
+    assert_eq!(
+        template::render("{ kind } {kind} {} {Kind} {kind", &vars),
+        "{ kind } idea {} {Kind} {kind"
+    );
+}
+
+#[test]
+fn test_template_check_rejects_unknown_and_unbalanced() {
+    let known = ["count", "topics"];
+    assert_eq!(
+        template::check("{count} {?topics}{topics}{/topics}", &known),
+        Ok(())
+    );
+    assert!(template::check("{cuont}", &known)
+        .unwrap_err()
+        .contains("unknown placeholder `{cuont}`"));
+    assert!(template::check("{?topics}x", &known)
+        .unwrap_err()
+        .contains("leaves section `topics` open"));
+    assert!(template::check("x{/topics}", &known)
+        .unwrap_err()
+        .contains("closes section `topics`"));
+}
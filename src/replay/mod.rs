@@ -0,0 +1,310 @@
+//! Replay — run the Stop gates over historical transcripts.
+//!
+//! Pure functions over transcript content; the CLI walks directories and
+//! reads files. Scope and hook guards are ignored: every transcript is
+//! judged as if it ended inside the `scope` rules.
+
+use crate::capture::{self, Request};
+use crate::config::{Config, StopGate};
+use crate::gate;
+use crate::transcript::{self, TranscriptAnalysis};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// Combined Stop verdict, decided the way the Stop hook decides: the gates
+/// in `stop_gates` order, then override phrases, then `block_budget`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "decision", content = "gates", rename_all = "snake_case")]
+pub enum Verdict {
+    Allow,
+    /// The gates block, but the latest prompt carries an override phrase.
+    Override(Vec<StopGate>),
+    /// The gates block, but the session already spent its `block_budget`.
+    BudgetSpent(Vec<StopGate>),
+    Block(Vec<StopGate>),
+}
+
+impl Verdict {
+    pub fn is_block(&self) -> bool {
+        matches!(self, Self::Block(_))
+    }
+
+    pub fn label(&self) -> String {
+        let (decision, gates) = match self {
+            Self::Allow => return "allow".to_string(),
+            Self::Override(gates) => ("override", gates),
+            Self::BudgetSpent(gates) => ("budget", gates),
+            Self::Block(gates) => ("block", gates),
+        };
+        let names: Vec<_> = gates.iter().map(|gate| gate.name()).collect();
+        format!("{decision}:{}", names.join("+"))
+    }
+}
+
+/// Stop verdict for one analyzed transcript. Capture requests are rebuilt
+/// from the transcript's prompts, and earlier blocks are counted from the
+/// Stop hook feedback the agent received.
+pub fn verdict(analysis: &TranscriptAnalysis, config: &Config) -> Verdict {
+    let cwd = analysis.cwd.as_deref().unwrap_or_default();
+    let requests = capture_requests(analysis, config, cwd);
+    let gates = gate::stop_blocks(analysis, config, &requests, cwd);
+    if gates.is_empty() {
+        Verdict::Allow
+    } else if analysis.user_override.is_some() {
+        Verdict::Override(gates)
+    } else if config.block_budget > 0
+        && !config.profile.audits()
+        && analysis.stop_feedback >= config.block_budget as usize
+    {
+        Verdict::BudgetSpent(gates)
+    } else {
+        Verdict::Block(gates)
+    }
+}
+
+/// The requests `UserPromptSubmit` would have recorded for the transcript's
+/// prompts.
+fn capture_requests(analysis: &TranscriptAnalysis, config: &Config, cwd: &str) -> Vec<Request> {
+    analysis
+        .prompts
+        .iter()
+        .filter_map(|(prompt, writes)| {
+            let (kind, text) = capture::detect(prompt, &config.capture)?;
+            let before = &analysis.file_writes[..*writes];
+            Some(Request {
+                kind,
+                text,
+                requested_at: 0,
+                writes_before: gate::capture_writes(kind, config, cwd, before),
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug, Serialize)]
+pub struct SessionReport {
+    pub session: String,
+    pub user_messages: usize,
+    pub tool_turns: usize,
+    pub duration_minutes: u64,
+    pub insights: usize,
+    pub uncaptured_topics: Vec<String>,
+    pub uncaptured_unnamed: usize,
+    /// Topics the `PreCompact` prompt would list as unwritten.
+    pub precompact_topics: usize,
+    pub verdict: Verdict,
+    /// Verdict under the comparison config, when one was given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub candidate: Option<Verdict>,
+}
+
+impl SessionReport {
+    pub fn uncaptured(&self) -> usize {
+        self.uncaptured_topics.len() + self.uncaptured_unnamed
+    }
+}
+
+/// Analyze one transcript under `config` (and optionally a `candidate` config).
+pub fn replay_session(
+    session: &str,
+    content: &str,
+    config: &Config,
+    candidate: Option<&Config>,
+) -> SessionReport {
+    let analysis = transcript::analyze_transcript(content, config);
+    let uncaptured = gate::uncaptured_insights(&analysis);
+    let candidate = candidate.map(|c| verdict(&transcript::analyze_transcript(content, c), c));
+
+    SessionReport {
+        session: session.to_string(),
+        user_messages: analysis.user_messages,
+        tool_turns: analysis.tool_using_turns,
+        duration_minutes: analysis.session_duration_minutes,
+        insights: analysis.insight_count,
+        uncaptured_topics: uncaptured.topics.iter().map(ToString::to_string).collect(),
+        uncaptured_unnamed: uncaptured.unnamed,
+        precompact_topics: gate::unwritten_topics(&analysis).len(),
+        verdict: verdict(&analysis, config),
+        candidate,
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct Tally {
+    pub blocked: usize,
+    pub insight_blocks: usize,
+    pub reflect_blocks: usize,
+    pub capture_blocks: usize,
+    /// Sessions the gates would block but an override phrase lets through.
+    pub overridden: usize,
+    /// Sessions the gates would block past their `block_budget`.
+    pub budget_spent: usize,
+}
+
+impl Tally {
+    fn add(&mut self, verdict: &Verdict) {
+        let gates = match verdict {
+            Verdict::Allow => return,
+            Verdict::Override(_) => {
+                self.overridden += 1;
+                return;
+            }
+            Verdict::BudgetSpent(_) => {
+                self.budget_spent += 1;
+                return;
+            }
+            Verdict::Block(gates) => gates,
+        };
+        self.blocked += 1;
+        for gate in gates {
+            match gate {
+                StopGate::Insight => self.insight_blocks += 1,
+                StopGate::Reflect => self.reflect_blocks += 1,
+                StopGate::Capture => self.capture_blocks += 1,
+            }
+        }
+    }
+
+    /// `"insight 1, reflect 2, capture 0"`, plus the blocks let through.
+    fn breakdown(&self) -> String {
+        let mut out = format!(
+            "insight {}, reflect {}, capture {}",
+            self.insight_blocks, self.reflect_blocks, self.capture_blocks
+        );
+        if self.overridden > 0 {
+            let _ = write!(out, "; {} overridden", self.overridden);
+        }
+        if self.budget_spent > 0 {
+            let _ = write!(out, "; {} past budget", self.budget_spent);
+        }
+        out
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Summary {
+    pub sessions: usize,
+    pub current: Tally,
+    pub insights: usize,
+    pub captured: usize,
+    /// Most common uncaptured topics (case-insensitive) with session counts.
+    pub top_uncaptured: Vec<(String, usize)>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub candidate: Option<Comparison>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct Comparison {
+    pub tally: Tally,
+    pub newly_blocked: Vec<String>,
+    pub newly_allowed: Vec<String>,
+}
+
+/// Aggregate session reports. `top` caps the uncaptured topic list.
+pub fn summarize(reports: &[SessionReport], top: usize) -> Summary {
+    let mut current = Tally::default();
+    let mut comparison: Option<Comparison> = None;
+    let mut insights = 0;
+    let mut uncaptured_total = 0;
+    let mut topic_counts: HashMap<String, (String, usize)> = HashMap::new();
+
+    for report in reports {
+        current.add(&report.verdict);
+        insights += report.insights;
+        uncaptured_total += report.uncaptured().min(report.insights);
+        for topic in &report.uncaptured_topics {
+            topic_counts
+                .entry(topic.to_lowercase())
+                .or_insert_with(|| (topic.clone(), 0))
+                .1 += 1;
+        }
+        if let Some(candidate) = &report.candidate {
+            let cmp = comparison.get_or_insert_with(Comparison::default);
+            cmp.tally.add(candidate);
+            match (report.verdict.is_block(), candidate.is_block()) {
+                (false, true) => cmp.newly_blocked.push(report.session.clone()),
+                (true, false) => cmp.newly_allowed.push(report.session.clone()),
+                _ => {}
+            }
+        }
+    }
+
+    let mut top_uncaptured: Vec<(String, usize)> = topic_counts.into_values().collect();
+    top_uncaptured.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    top_uncaptured.truncate(top);
+
+    Summary {
+        sessions: reports.len(),
+        current,
+        insights,
+        captured: insights - uncaptured_total,
+        top_uncaptured,
+        candidate: comparison,
+    }
+}
+
+/// Integer percentage, 0 when the denominator is 0.
+fn percent(part: usize, whole: usize) -> usize {
+    (part * 100).checked_div(whole).unwrap_or(0)
+}
+
+/// Per-session table followed by the aggregate summary.
+pub fn render_text(reports: &[SessionReport], summary: &Summary) -> String {
+    let mut out = String::new();
+    for r in reports {
+        let _ = write!(
+            out,
+            "{:<14} {} ({} msgs, {} tool turns, {} min, {} insight(s), {} uncaptured)",
+            r.verdict.label(),
+            r.session,
+            r.user_messages,
+            r.tool_turns,
+            r.duration_minutes,
+            r.insights,
+            r.uncaptured()
+        );
+        if let Some(candidate) = r.candidate.as_ref().filter(|c| **c != r.verdict) {
+            let _ = write!(out, " \u{2192} {}", candidate.label());
+        }
+        out.push('\n');
+    }
+
+    let s = summary;
+    let _ = writeln!(out, "\nSessions:     {}", s.sessions);
+    let _ = writeln!(
+        out,
+        "Would block:  {} ({}%) \u{2014} {}",
+        s.current.blocked,
+        percent(s.current.blocked, s.sessions),
+        s.current.breakdown()
+    );
+    let _ = writeln!(
+        out,
+        "Capture rate: {}/{} insights ({}%)",
+        s.captured,
+        s.insights,
+        percent(s.captured, s.insights)
+    );
+    if !s.top_uncaptured.is_empty() {
+        out.push_str("Top uncaptured topics:\n");
+        for (topic, count) in &s.top_uncaptured {
+            let _ = writeln!(out, "  \u{2022} {topic} ({count})");
+        }
+    }
+    if let Some(cmp) = &s.candidate {
+        let _ = writeln!(
+            out,
+            "Candidate:    {} ({}%) \u{2014} {}; {} newly blocked, {} newly allowed",
+            cmp.tally.blocked,
+            percent(cmp.tally.blocked, s.sessions),
+            cmp.tally.breakdown(),
+            cmp.newly_blocked.len(),
+            cmp.newly_allowed.len()
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests;
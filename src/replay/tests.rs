@@ -0,0 +1,228 @@
+use super::*;
+
+fn user(ts: &str) -> String {
+    serde_json::json!({ "type": "user", "timestamp": ts }).to_string()
+}
+
+fn prompt(ts: &str, text: &str) -> String {
+    serde_json::json!({
+        "type": "user",
+        "timestamp": ts,
+        "message": { "content": text }
+    })
+    .to_string()
+}
+
+fn assistant(text: &str) -> String {
+    serde_json::json!({
+        "type": "assistant",
+        "message": { "content": [
+            { "type": "text", "text": text },
+            { "type": "tool_use", "name": "Read", "input": { "file_path": "/x" } }
+        ]}
+    })
+    .to_string()
+}
+
+fn write(path: &str) -> String {
+    serde_json::json!({
+        "type": "assistant",
+        "message": { "content": [
+            { "type": "tool_use", "name": "Write", "input": { "file_path": path } }
+        ]}
+    })
+    .to_string()
+}
+
+/// 4 user messages over 30 minutes, 10 tool turns, optional insight text.
+fn session(insight: &str, extra: &[String]) -> String {
+    let mut lines = vec![
+        user("2026-01-01T10:00:00Z"),
+        user("2026-01-01T10:10:00Z"),
+        user("2026-01-01T10:20:00Z"),
+        user("2026-01-01T10:30:00Z"),
+        assistant(insight),
+    ];
+    for _ in 0..9 {
+        lines.push(assistant("working"));
+    }
+    lines.extend_from_slice(extra);
+    lines.join("\n")
+}
+
+#[test]
+fn test_uncaptured_insight_blocks_every_gate_in_order() {
+    let report = replay_session(
+        "a.jsonl",
+        &session("\u{2605} Insight: Config Merge Order", &[]),
+        &Config::default(),
+        None,
+    );
+    assert_eq!(
+        report.verdict,
+        Verdict::Block(vec![StopGate::Insight, StopGate::Reflect])
+    );
+    assert_eq!(report.verdict.label(), "block:insight+reflect");
+    assert_eq!(report.uncaptured_topics, vec!["Config Merge Order"]);
+    assert_eq!(report.precompact_topics, 1);
+}
+
+#[test]
+fn test_no_memory_write_blocks_reflect() {
+    let report = replay_session("b.jsonl", &session("plain", &[]), &Config::default(), None);
+    assert_eq!(report.verdict, Verdict::Block(vec![StopGate::Reflect]));
+}
+
+#[test]
+fn test_captured_session_allowed() {
+    let content = session(
+        "\u{2605} Insight: Config Merge Order",
+        &[write("/v/Memory/Insights/Config Merge Order.md")],
+    );
+    let report = replay_session("c.jsonl", &content, &Config::default(), None);
+    assert_eq!(report.verdict, Verdict::Allow);
+    assert_eq!(report.uncaptured(), 0);
+}
+
+#[test]
+fn test_candidate_config_changes_verdict() {
+    let candidate = Config {
+        duration_threshold_minutes: 60,
+        ..Config::default()
+    };
+    let report = replay_session(
+        "d.jsonl",
+        &session("plain", &[]),
+        &Config::default(),
+        Some(&candidate),
+    );
+    assert_eq!(report.verdict, Verdict::Block(vec![StopGate::Reflect]));
+    assert_eq!(report.candidate, Some(Verdict::Allow));
+
+    let summary = summarize(&[report], 10);
+    let cmp = summary.candidate.unwrap();
+    assert_eq!(cmp.tally.blocked, 0);
+    assert_eq!(cmp.newly_allowed, vec!["d.jsonl"]);
+}
+
+#[test]
+fn test_candidate_stop_gates_drop_insight() {
+    let candidate = Config {
+        stop_gates: vec![StopGate::Reflect],
+        ..Config::default()
+    };
+    let content = session(
+        "\u{2605} Insight: Config Merge Order",
+        &[write("/v/Memory/Insights/Unrelated Note.md")],
+    );
+    let report = replay_session("e.jsonl", &content, &Config::default(), Some(&candidate));
+    assert_eq!(report.verdict, Verdict::Block(vec![StopGate::Insight]));
+    assert_eq!(report.candidate, Some(Verdict::Allow));
+
+    let summary = summarize(&[report], 10);
+    assert_eq!(summary.current.insight_blocks, 1);
+    assert_eq!(summary.candidate.unwrap().newly_allowed, vec!["e.jsonl"]);
+}
+
+#[test]
+fn test_override_phrase_allows_blocking_gates() {
+    let content = session(
+        "plain",
+        &[prompt("2026-01-01T10:40:00Z", "/NoReflect quick fix only")],
+    );
+    let report = replay_session("f.jsonl", &content, &Config::default(), None);
+    assert_eq!(report.verdict, Verdict::Override(vec![StopGate::Reflect]));
+    assert!(!report.verdict.is_block());
+
+    let summary = summarize(&[report], 10);
+    assert_eq!(summary.current.blocked, 0);
+    assert_eq!(summary.current.overridden, 1);
+}
+
+#[test]
+fn test_spent_block_budget_allows() {
+    let feedback: Vec<String> = (0..3)
+        .map(|_| {
+            prompt(
+                "2026-01-01T10:40:00Z",
+                "Stop hook feedback:\nReflect first.",
+            )
+        })
+        .collect();
+    let content = session("plain", &feedback);
+    let report = replay_session("g.jsonl", &content, &Config::default(), None);
+    assert_eq!(
+        report.verdict,
+        Verdict::BudgetSpent(vec![StopGate::Reflect])
+    );
+
+    let unlimited = Config {
+        block_budget: 0,
+        ..Config::default()
+    };
+    let report = replay_session("g.jsonl", &content, &unlimited, None);
+    assert_eq!(report.verdict, Verdict::Block(vec![StopGate::Reflect]));
+}
+
+#[test]
+fn test_capture_request_without_file_blocks() {
+    let config = Config {
+        stop_gates: vec![StopGate::Capture],
+        ..Config::default()
+    };
+    let content = session(
+        "plain",
+        &[prompt(
+            "2026-01-01T10:40:00Z",
+            "Remember that release builds need --release.",
+        )],
+    );
+    let report = replay_session("h.jsonl", &content, &config, None);
+    assert_eq!(report.verdict, Verdict::Block(vec![StopGate::Capture]));
+
+    let quiet = replay_session("h.jsonl", &session("plain", &[]), &config, None);
+    assert_eq!(quiet.verdict, Verdict::Allow);
+}
+
+#[test]
+fn test_summary_rates_and_top_topics() {
+    let config = Config::default();
+    let reports = vec![
+        replay_session(
+            "a.jsonl",
+            &session("\u{2605} Insight: Config Merge Order", &[]),
+            &config,
+            None,
+        ),
+        replay_session(
+            "b.jsonl",
+            &session("\u{2605} Insight: config merge order", &[]),
+            &config,
+            None,
+        ),
+        replay_session(
+            "c.jsonl",
+            &session(
+                "\u{2605} Insight: Regex Anchoring Rules",
+                &[write("/v/Memory/Insights/Regex Anchoring Rules.md")],
+            ),
+            &config,
+            None,
+        ),
+    ];
+    let summary = summarize(&reports, 10);
+    assert_eq!(summary.sessions, 3);
+    assert_eq!(summary.current.blocked, 2);
+    assert_eq!(summary.current.insight_blocks, 2);
+    assert_eq!(summary.insights, 3);
+    assert_eq!(summary.captured, 1);
+    assert_eq!(
+        summary.top_uncaptured,
+        vec![("Config Merge Order".to_string(), 2)]
+    );
+    assert!(summary.candidate.is_none());
+
+    let text = render_text(&reports, &summary);
+    assert!(text.contains("Would block:  2 (66%)"));
+    assert!(text.contains("Capture rate: 1/3 insights (33%)"));
+}
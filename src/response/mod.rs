@@ -0,0 +1,199 @@
+//! Host-agnostic hook responses.
+//!
+//! Commands decide *what* to say as a [`HookResponse`]; [`HookResponse::render`]
+//! decides *how* for the AI tool running the hook. No I/O.
+
+use crate::HookInput;
+use serde_json::{json, Value};
+
+/// The AI coding tool that invoked the hook.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Host {
+    /// Claude Code hooks.
+    #[default]
+    Claude,
+    /// Codex CLI `notify` program — fire-and-forget, output is a record.
+    Codex,
+    /// Gemini CLI hooks.
+    Gemini,
+    /// The opencode plugin adapter in `.opencode/plugins/`.
+    Opencode,
+}
+
+/// Gemini CLI event names with no Claude Code equivalent of the same name.
+const GEMINI_EVENTS: [&str; 8] = [
+    "BeforeAgent",
+    "AfterAgent",
+    "BeforeTool",
+    "AfterTool",
+    "BeforeModel",
+    "AfterModel",
+    "BeforeToolSelection",
+    "PreCompress",
+];
+
+impl Host {
+    /// Guess the host from a hook payload: Codex `notify` payloads carry
+    /// `"type": "agent-turn-complete"`, Gemini uses its own event names, and
+    /// transcripts live under each tool's home directory. Claude otherwise.
+    pub fn detect(input: &HookInput) -> Self {
+        if input.event_type == "agent-turn-complete" {
+            return Self::Codex;
+        }
+        if GEMINI_EVENTS.contains(&input.hook_event_name.as_str()) {
+            return Self::Gemini;
+        }
+        if input.transcript_path.contains("/.gemini/") {
+            Self::Gemini
+        } else if input.transcript_path.contains("/.codex/") {
+            Self::Codex
+        } else {
+            Self::Claude
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Claude => "claude",
+            Self::Codex => "codex",
+            Self::Gemini => "gemini",
+            Self::Opencode => "opencode",
+        }
+    }
+}
+
+/// What a hook command wants to tell the host.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HookResponse {
+    /// Let the action proceed; nothing to say.
+    Allow,
+    /// Refuse to stop; `reason` is fed back to the model.
+    Block { reason: String },
+    /// Add `text` to the model's context. `event` is the Claude Code event
+    /// name (`PostToolUse`, `PreCompact`, `SessionStart`, ...).
+    InjectContext { event: String, text: String },
+    /// Show `message` to the user without affecting the model.
+    Notify { message: String },
+}
+
+impl HookResponse {
+    /// JSON for `host`'s hook protocol. None = print nothing (allow).
+    pub fn render(&self, host: Host) -> Option<Value> {
+        match host {
+            Host::Claude => self.render_claude(),
+            Host::Codex => self.render_codex(),
+            Host::Gemini => self.render_gemini(),
+            Host::Opencode => self.render_opencode(),
+        }
+    }
+
+    /// Like [`HookResponse::render`], with `notice` also shown to the user —
+    /// as `systemMessage` where the host has one, else a `notice` field (or
+    /// appended to an opencode toast). Alone, the notice renders as
+    /// [`HookResponse::Notify`].
+    pub fn render_with_notice(&self, host: Host, notice: Option<&str>) -> Option<Value> {
+        let Some(notice) = notice else {
+            return self.render(host);
+        };
+        let Some(mut json) = self.render(host) else {
+            return Self::Notify {
+                message: notice.to_string(),
+            }
+            .render(host);
+        };
+        let key = match host {
+            Host::Claude | Host::Gemini => "systemMessage",
+            Host::Opencode if json["action"] == "toast" => "message",
+            Host::Codex | Host::Opencode => "notice",
+        };
+        json[key] = match json.get(key).and_then(Value::as_str) {
+            Some(existing) => Value::String(format!("{existing}\n{notice}")),
+            None => Value::String(notice.to_string()),
+        };
+        Some(json)
+    }
+
+    fn render_claude(&self) -> Option<Value> {
+        Some(match self {
+            Self::Allow => return None,
+            Self::Block { reason } => json!({ "decision": "block", "reason": reason }),
+            // PreCompact has no hookSpecificOutput schema.
+            Self::InjectContext { event, text } if event == "PreCompact" => {
+                json!({ "additionalContext": text })
+            }
+            Self::InjectContext { event, text } => json!({
+                "hookSpecificOutput": { "hookEventName": event, "additionalContext": text }
+            }),
+            Self::Notify { message } => json!({ "systemMessage": message }),
+        })
+    }
+
+    /// Gemini CLI: `deny` on `AfterAgent` sends the reason back as a retry
+    /// prompt; events are renamed to Gemini's.
+    fn render_gemini(&self) -> Option<Value> {
+        Some(match self {
+            Self::Allow => return None,
+            Self::Block { reason } => json!({ "decision": "deny", "reason": reason }),
+            Self::InjectContext { event, text } => json!({
+                "hookSpecificOutput": {
+                    "hookEventName": gemini_event(event),
+                    "additionalContext": text
+                }
+            }),
+            Self::Notify { message } => json!({ "systemMessage": message }),
+        })
+    }
+
+    /// Codex ignores `notify` output, so the response is a record for the
+    /// caller to persist and replay at the next session.
+    fn render_codex(&self) -> Option<Value> {
+        Some(match self {
+            Self::Allow => return None,
+            Self::Block { reason } => json!({ "type": "block", "reason": reason }),
+            Self::InjectContext { event, text } => {
+                json!({ "type": "context", "event": event, "text": text })
+            }
+            Self::Notify { message } => json!({ "type": "notify", "message": message }),
+        })
+    }
+
+    /// opencode adapter: blocks and notices become TUI toasts, context is
+    /// pushed into the session.
+    fn render_opencode(&self) -> Option<Value> {
+        Some(match self {
+            Self::Allow => return None,
+            Self::Block { reason } => {
+                json!({ "action": "toast", "level": "warn", "message": reason })
+            }
+            Self::InjectContext { text, .. } => json!({ "action": "context", "text": text }),
+            Self::Notify { message } => {
+                json!({ "action": "toast", "level": "info", "message": message })
+            }
+        })
+    }
+
+    /// Human-readable form for `--format text`. None = print nothing.
+    pub fn render_text(&self) -> Option<String> {
+        match self {
+            Self::Allow => None,
+            Self::Block { reason } => Some(format!("blocked: {reason}")),
+            Self::InjectContext { text, .. } => Some(text.clone()),
+            Self::Notify { message } => Some(message.clone()),
+        }
+    }
+}
+
+/// Claude Code event name → Gemini CLI event name.
+fn gemini_event(event: &str) -> &str {
+    match event {
+        "PostToolUse" => "AfterTool",
+        "PreToolUse" => "BeforeTool",
+        "PreCompact" => "PreCompress",
+        "Stop" => "AfterAgent",
+        "UserPromptSubmit" => "BeforeAgent",
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests;
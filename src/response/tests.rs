@@ -0,0 +1,115 @@
+use super::*;
+
+fn block() -> HookResponse {
+    HookResponse::Block {
+        reason: "capture it".to_string(),
+    }
+}
+
+fn context(event: &str) -> HookResponse {
+    HookResponse::InjectContext {
+        event: event.to_string(),
+        text: "digest".to_string(),
+    }
+}
+
+#[test]
+fn test_allow_renders_nothing_for_every_host() {
+    for host in [Host::Claude, Host::Codex, Host::Gemini, Host::Opencode] {
+        assert_eq!(HookResponse::Allow.render(host), None);
+    }
+    assert_eq!(HookResponse::Allow.render_text(), None);
+}
+
+#[test]
+fn test_claude_shapes() {
+    assert_eq!(
+        block().render(Host::Claude).unwrap(),
+        json!({ "decision": "block", "reason": "capture it" })
+    );
+    assert_eq!(
+        context("PostToolUse").render(Host::Claude).unwrap(),
+        json!({ "hookSpecificOutput": {
+            "hookEventName": "PostToolUse", "additionalContext": "digest"
+        }})
+    );
+    assert_eq!(
+        context("PreCompact").render(Host::Claude).unwrap(),
+        json!({ "additionalContext": "digest" })
+    );
+}
+
+#[test]
+fn test_gemini_renames_events_and_denies() {
+    assert_eq!(block().render(Host::Gemini).unwrap()["decision"], "deny");
+    assert_eq!(
+        context("PostToolUse").render(Host::Gemini).unwrap()["hookSpecificOutput"]["hookEventName"],
+        "AfterTool"
+    );
+}
+
+#[test]
+fn test_opencode_and_codex_records() {
+    assert_eq!(
+        block().render(Host::Opencode).unwrap(),
+        json!({ "action": "toast", "level": "warn", "message": "capture it" })
+    );
+    assert_eq!(
+        context("PreCompact").render(Host::Opencode).unwrap(),
+        json!({ "action": "context", "text": "digest" })
+    );
+    assert_eq!(block().render(Host::Codex).unwrap()["type"], "block");
+}
+
+#[test]
+fn test_detect_host() {
+    let input = |event: &str, kind: &str, transcript: &str| HookInput {
+        hook_event_name: event.to_string(),
+        event_type: kind.to_string(),
+        transcript_path: transcript.to_string(),
+        ..HookInput::default()
+    };
+    assert_eq!(
+        Host::detect(&input("Stop", "", "/h/.claude/p/s.jsonl")),
+        Host::Claude
+    );
+    assert_eq!(Host::detect(&input("AfterAgent", "", "")), Host::Gemini);
+    assert_eq!(
+        Host::detect(&input("", "", "/h/.gemini/tmp/c.json")),
+        Host::Gemini
+    );
+    assert_eq!(
+        Host::detect(&input("", "agent-turn-complete", "")),
+        Host::Codex
+    );
+    assert_eq!(Host::detect(&HookInput::default()), Host::Claude);
+}
+
+#[test]
+fn test_notice_rides_along_or_stands_alone() {
+    let notice = Some("config: bad key");
+    assert_eq!(
+        block().render_with_notice(Host::Claude, notice).unwrap(),
+        json!({ "decision": "block", "reason": "capture it", "systemMessage": "config: bad key" })
+    );
+    assert_eq!(
+        HookResponse::Allow
+            .render_with_notice(Host::Gemini, notice)
+            .unwrap(),
+        json!({ "systemMessage": "config: bad key" })
+    );
+    assert_eq!(
+        context("SessionStart")
+            .render_with_notice(Host::Codex, notice)
+            .unwrap()["notice"],
+        "config: bad key"
+    );
+    assert_eq!(
+        block().render_with_notice(Host::Opencode, notice).unwrap()["message"],
+        "capture it\nconfig: bad key"
+    );
+    assert_eq!(
+        HookResponse::Allow.render_with_notice(Host::Claude, None),
+        None
+    );
+}
@@ -0,0 +1,98 @@
+//! Which sessions the Stop gates apply to — ordered include/exclude glob
+//! rules over the cwd, each able to pick an enforcement [`Profile`].
+//!
+//! No I/O — `$HOME` is passed in.
+
+use crate::config::{Profile, ScopeRule};
+
+/// Outcome of matching a cwd against the `scope` rules.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scope {
+    /// The gates apply to this cwd.
+    pub included: bool,
+    /// 1-based index and glob of the matching rule; None = no rule matched.
+    pub rule: Option<(usize, String)>,
+    /// Profile of the matching include rule.
+    pub profile: Option<Profile>,
+}
+
+impl Scope {
+    /// One-line account for `--explain`.
+    pub fn describe(&self) -> String {
+        let Some((index, glob)) = &self.rule else {
+            return "no scope rule matches".to_string();
+        };
+        let verb = if self.included { "include" } else { "exclude" };
+        let profile = self
+            .profile
+            .map(|p| format!(", profile {}", p.name()))
+            .unwrap_or_default();
+        format!("rule {index}: {verb} {glob}{profile}")
+    }
+}
+
+/// First rule whose glob matches `cwd` decides; no match is out of scope.
+/// Rules with neither or both of `include` / `exclude` are skipped.
+pub fn evaluate(rules: &[ScopeRule], cwd: &str, home: &str) -> Scope {
+    let cwd = cwd.trim_end_matches('/');
+    for (index, rule) in rules.iter().enumerate() {
+        let (glob, included) = match (&rule.include, &rule.exclude) {
+            (Some(glob), None) => (glob, true),
+            (None, Some(glob)) => (glob, false),
+            _ => continue,
+        };
+        if !cwd.is_empty() && matches(glob, cwd, home) {
+            return Scope {
+                included,
+                rule: Some((index + 1, glob.clone())),
+                profile: rule.profile.filter(|_| included),
+            };
+        }
+    }
+    Scope {
+        included: false,
+        rule: None,
+        profile: None,
+    }
+}
+
+/// Does `glob` match the whole of `path`? A leading `~` expands to `home`
+/// and never matches when `home` is empty.
+pub fn matches(glob: &str, path: &str, home: &str) -> bool {
+    let glob = match glob.strip_prefix('~') {
+        Some(_) if home.is_empty() => return false,
+        Some(rest) => format!("{}{rest}", home.trim_end_matches('/')),
+        None => glob.to_string(),
+    };
+    regex::Regex::new(&to_regex(glob.trim_end_matches('/'))).is_ok_and(|re| re.is_match(path))
+}
+
+fn to_regex(glob: &str) -> String {
+    let mut re = String::from("^");
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                // `dir/**` matches `dir` too; `**/x` matches `x` at any depth.
+                if re.ends_with('/') && chars.peek().is_none() {
+                    re.pop();
+                    re.push_str("(?:/.*)?");
+                } else if chars.peek() == Some(&'/') {
+                    chars.next();
+                    re.push_str("(?:.*/)?");
+                } else {
+                    re.push_str(".*");
+                }
+            }
+            '*' => re.push_str("[^/]*"),
+            '?' => re.push_str("[^/]"),
+            c => re.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    re.push('$');
+    re
+}
+
+#[cfg(test)]
+mod tests;
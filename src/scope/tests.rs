@@ -0,0 +1,94 @@
+use super::*;
+
+const HOME: &str = "/Users/test";
+
+fn include(glob: &str) -> ScopeRule {
+    ScopeRule {
+        include: Some(glob.to_string()),
+        ..ScopeRule::default()
+    }
+}
+
+fn exclude(glob: &str) -> ScopeRule {
+    ScopeRule {
+        exclude: Some(glob.to_string()),
+        ..ScopeRule::default()
+    }
+}
+
+#[test]
+fn test_exact_match_and_subdirectory() {
+    let rules = [include("~/Data/**")];
+    assert!(evaluate(&rules, "/Users/test/Data", HOME).included);
+    assert!(evaluate(&rules, "/Users/test/Data/", HOME).included);
+    assert!(evaluate(&rules, "/Users/test/Data/Plugins", HOME).included);
+}
+
+#[test]
+fn test_prefix_collision_rejected() {
+    let rules = [include("~/Data/**")];
+    assert!(!evaluate(&rules, "/Users/test/DataBackup", HOME).included);
+    assert!(!evaluate(&rules, "/Users/test/Projects", HOME).included);
+}
+
+#[test]
+fn test_empty_home_never_matches_tilde() {
+    assert!(!evaluate(&[include("~/Data/**")], "/Data", "").included);
+    assert!(evaluate(&[include("/Data/**")], "/Data", "").included);
+}
+
+#[test]
+fn test_first_matching_rule_wins() {
+    let rules = [
+        exclude("~/src/scratch/**"),
+        ScopeRule {
+            include: Some("~/src/**".to_string()),
+            profile: Some(Profile::Advisory),
+            ..ScopeRule::default()
+        },
+        include("/tmp/keep"),
+        exclude("/tmp/**"),
+    ];
+    let scratch = evaluate(&rules, "/Users/test/src/scratch/x", HOME);
+    assert!(!scratch.included);
+    assert_eq!(scratch.profile, None);
+    assert_eq!(scratch.describe(), "rule 1: exclude ~/src/scratch/**");
+
+    let repo = evaluate(&rules, "/Users/test/src/forge", HOME);
+    assert!(repo.included);
+    assert_eq!(repo.profile, Some(Profile::Advisory));
+    assert_eq!(
+        repo.describe(),
+        "rule 2: include ~/src/**, profile advisory"
+    );
+
+    assert!(evaluate(&rules, "/tmp/keep", HOME).included);
+    assert!(!evaluate(&rules, "/tmp/other", HOME).included);
+    assert_eq!(
+        evaluate(&rules, "/var", HOME).describe(),
+        "no scope rule matches"
+    );
+}
+
+#[test]
+fn test_glob_segments() {
+    assert!(matches("/work/*/docs", "/work/a/docs", HOME));
+    assert!(!matches("/work/*/docs", "/work/a/b/docs", HOME));
+    assert!(matches("/work/**/docs", "/work/a/b/docs", HOME));
+    assert!(matches("/work/**/docs", "/work/docs", HOME));
+    assert!(matches("/work/repo-?", "/work/repo-1", HOME));
+    assert!(!matches("/work/a.b", "/work/axb", HOME));
+}
+
+#[test]
+fn test_malformed_rules_are_skipped() {
+    let both = ScopeRule {
+        include: Some("/a/**".to_string()),
+        exclude: Some("/a/**".to_string()),
+        profile: None,
+    };
+    assert_eq!(
+        evaluate(&[both, ScopeRule::default()], "/a", HOME).rule,
+        None
+    );
+}
@@ -0,0 +1,378 @@
+//! Per-session state — one JSON document per session under
+//! `$XDG_STATE_HOME/forge-reflect/` (default `~/.local/state/forge-reflect/`).
+//!
+//! Hooks for one session can run concurrently, so every update holds an
+//! exclusive lock on `<session>.lock` and replaces `<session>.json` via
+//! write-then-rename.
+
+use crate::advisory::Counters;
+use crate::capture;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Bumped when a field changes meaning. Documents with another version are
+/// discarded rather than misread.
+pub const SCHEMA_VERSION: u32 = 1;
+
+const LEDGER: &str = "ledger.jsonl";
+const OVERRIDES: &str = "overrides.jsonl";
+const AUDIT: &str = "audit.jsonl";
+
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SessionState {
+    pub version: u32,
+    pub session_id: String,
+    /// Unix seconds of the last update.
+    pub updated_at: i64,
+    /// The `posttooluse` surface digest has been shown.
+    pub surface_shown: bool,
+    /// `posttooluse` advisory trigger counters.
+    pub advisory: Counters,
+    /// Blocks the `stop` command has emitted this session.
+    pub stop_blocks: u32,
+    /// The block budget ran out and the obligations went to the ledger.
+    pub budget_exhausted: bool,
+    /// Uncaptured topics handed to `/MemoryCapture` by the last `PreCompact`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub precompact_candidates: Option<serde_json::Value>,
+    /// "Remember this" requests from user prompts, oldest first.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub capture_requests: Vec<capture::Request>,
+    /// Obligations from the last Codex turn that would have blocked a Stop.
+    /// Codex `notify` cannot block, so the Codex skill reads this instead.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pending_reflection: Option<PendingReflection>,
+    /// Config problems last shown to the user, so each set is shown once.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub config_issues: Vec<String>,
+    /// Codex rollout `notify` analysed for this thread, so later turns skip
+    /// the search.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rollout: Option<String>,
+    /// User message of the last override written to `overrides.jsonl`, so
+    /// repeated Stop or `notify` runs in one turn log it once.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logged_override: Option<usize>,
+}
+
+/// What `notify` found outstanding after a Codex turn.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PendingReflection {
+    /// Unix seconds.
+    pub recorded_at: i64,
+    pub cwd: String,
+    /// Rollout file the analysis ran on.
+    pub transcript: String,
+    /// One line per blocking gate, as in [`LedgerEntry::obligations`].
+    pub obligations: Vec<String>,
+    /// The block reason a Stop hook would have shown.
+    pub reason: String,
+}
+
+/// Obligations a session ended with after its block budget ran out.
+/// Appended to `ledger.jsonl` and shown at the next session start.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct LedgerEntry {
+    pub session_id: String,
+    /// Unix seconds.
+    pub recorded_at: i64,
+    pub cwd: String,
+    /// One line per blocking gate, e.g. "insight: 2 uncaptured insight(s): a, b".
+    pub obligations: Vec<String>,
+}
+
+/// A Stop the user let through with an override phrase. Appended to
+/// `overrides.jsonl`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct OverrideRecord {
+    pub session_id: String,
+    /// Unix seconds.
+    pub recorded_at: i64,
+    pub cwd: String,
+    /// Command that allowed the stop: `stop`, `insight` or `reflect`.
+    pub gate: String,
+    pub phrase: String,
+    pub reason: String,
+    /// What would otherwise have blocked, as in [`LedgerEntry::obligations`].
+    pub bypassed: Vec<String>,
+}
+
+/// Hook output the `audit-only` profile withheld from stdout. Appended to
+/// `audit.jsonl`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct AuditRecord {
+    pub session_id: String,
+    /// Unix seconds.
+    pub recorded_at: i64,
+    pub cwd: String,
+    /// Subcommand that produced the output, e.g. `stop`.
+    pub command: String,
+    /// What would have been printed: the hook JSON, or text as a string.
+    pub output: serde_json::Value,
+}
+
+/// A directory of session documents.
+#[derive(Debug, Clone)]
+pub struct Store {
+    dir: PathBuf,
+    retention_days: u32,
+}
+
+impl Store {
+    /// `retention_days` of 0 disables garbage collection.
+    pub fn new(dir: impl Into<PathBuf>, retention_days: u32) -> Self {
+        Self {
+            dir: dir.into(),
+            retention_days,
+        }
+    }
+
+    /// `dir`, or `$XDG_STATE_HOME/forge-reflect`, or
+    /// `$HOME/.local/state/forge-reflect`, or the system temp dir.
+    pub fn resolve_dir(dir: &str) -> PathBuf {
+        if !dir.is_empty() {
+            return PathBuf::from(dir);
+        }
+        let non_empty = |var| std::env::var(var).ok().filter(|v: &String| !v.is_empty());
+        non_empty("XDG_STATE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| non_empty("HOME").map(|home| Path::new(&home).join(".local/state")))
+            .unwrap_or_else(std::env::temp_dir)
+            .join("forge-reflect")
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Document path for `session`. Characters outside `[A-Za-z0-9_-]` are
+    /// replaced so a session id cannot escape the directory.
+    pub fn path(&self, session: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", file_stem(session)))
+    }
+
+    /// Current state for `session` — default when absent, unreadable or
+    /// from another schema version. Does not lock; use [`Store::update`] to
+    /// modify.
+    pub fn load(&self, session: &str) -> SessionState {
+        read_state(&self.path(session)).unwrap_or_else(|| SessionState {
+            session_id: session.to_string(),
+            ..SessionState::default()
+        })
+    }
+
+    /// Apply `f` to the session's state under an exclusive lock and persist
+    /// the result atomically. The first write of a session also collects
+    /// documents older than `retention_days`.
+    pub fn update<T>(
+        &self,
+        session: &str,
+        now: i64,
+        f: impl FnOnce(&mut SessionState) -> T,
+    ) -> io::Result<T> {
+        fs::create_dir_all(&self.dir)?;
+        let lock = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.dir.join(format!("{}.lock", file_stem(session))))?;
+        lock.lock()?;
+
+        let path = self.path(session);
+        let existing = read_state(&path);
+        let is_new = existing.is_none();
+        let mut state = existing.unwrap_or_default();
+        let result = f(&mut state);
+        state.version = SCHEMA_VERSION;
+        session.clone_into(&mut state.session_id);
+        state.updated_at = now;
+
+        let tmp = path.with_extension(format!("json.{}.tmp", std::process::id()));
+        fs::write(&tmp, serde_json::to_vec_pretty(&state)?)?;
+        fs::rename(&tmp, &path)?;
+        drop(lock);
+
+        if is_new {
+            let _ = self.gc(SystemTime::now());
+        }
+        Ok(result)
+    }
+
+    /// Append `entry` to the ledger.
+    pub fn append_ledger(&self, entry: &LedgerEntry) -> io::Result<()> {
+        let _lock = self.lock_logs()?;
+        self.append_jsonl(LEDGER, entry)
+    }
+
+    /// Append `record` to the override audit log.
+    pub fn append_override(&self, record: &OverrideRecord) -> io::Result<()> {
+        let _lock = self.lock_logs()?;
+        self.append_jsonl(OVERRIDES, record)
+    }
+
+    /// Append `record` to the audit-only log.
+    pub fn append_audit(&self, record: &AuditRecord) -> io::Result<()> {
+        let _lock = self.lock_logs()?;
+        self.append_jsonl(AUDIT, record)
+    }
+
+    fn append_jsonl(&self, name: &str, value: &impl Serialize) -> io::Result<()> {
+        let mut line = serde_json::to_string(value)?;
+        line.push('\n');
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.dir.join(name))?
+            .write_all(line.as_bytes())
+    }
+
+    /// Every ledger entry, left in place. Malformed lines are dropped.
+    pub fn ledger(&self) -> io::Result<Vec<LedgerEntry>> {
+        let _lock = self.lock_logs()?;
+        self.read_ledger(false)
+    }
+
+    /// Remove and return every ledger entry. Malformed lines are dropped.
+    pub fn take_ledger(&self) -> io::Result<Vec<LedgerEntry>> {
+        let _lock = self.lock_logs()?;
+        self.read_ledger(true)
+    }
+
+    fn read_ledger(&self, remove: bool) -> io::Result<Vec<LedgerEntry>> {
+        let path = self.dir.join(LEDGER);
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        if remove {
+            fs::remove_file(&path)?;
+        }
+        Ok(content
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+
+    /// Serialises writers of the shared `*.jsonl` logs.
+    fn lock_logs(&self) -> io::Result<File> {
+        fs::create_dir_all(&self.dir)?;
+        let lock = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.dir.join("logs.lock"))?;
+        lock.lock()?;
+        Ok(lock)
+    }
+
+    /// Remove session documents (and their lock files) last modified more
+    /// than `retention_days` before `now`, and log entries recorded before
+    /// then. A `*.json` file is only taken for a session document when it
+    /// has a lock file or parses as one, so a misconfigured `state.dir`
+    /// cannot cost the user their files. Returns the number of sessions
+    /// removed.
+    pub fn gc(&self, now: SystemTime) -> io::Result<usize> {
+        if self.retention_days == 0 {
+            return Ok(0);
+        }
+        let max_age = Duration::from_secs(u64::from(self.retention_days) * 86_400);
+        let cutoff = now
+            .checked_sub(max_age)
+            .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+            .map_or(0, |t| i64::try_from(t.as_secs()).unwrap_or(i64::MAX));
+        self.trim_logs(cutoff)?;
+        let mut removed = 0;
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|e| e != "json") {
+                continue;
+            }
+            let stale = fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|modified| now.duration_since(modified).ok())
+                .is_some_and(|age| age > max_age);
+            if stale && is_session_document(&path) && fs::remove_file(&path).is_ok() {
+                let _ = fs::remove_file(path.with_extension("lock"));
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Drop entries of the `*.jsonl` logs recorded before `cutoff` (Unix
+    /// seconds). Lines without a `recorded_at` are kept.
+    fn trim_logs(&self, cutoff: i64) -> io::Result<()> {
+        let _lock = self.lock_logs()?;
+        for name in [LEDGER, OVERRIDES, AUDIT] {
+            let path = self.dir.join(name);
+            let Ok(content) = fs::read_to_string(&path) else {
+                continue;
+            };
+            let kept: Vec<&str> = content
+                .lines()
+                .filter(|line| {
+                    serde_json::from_str::<serde_json::Value>(line)
+                        .ok()
+                        .and_then(|entry| entry["recorded_at"].as_i64())
+                        .is_none_or(|at| at >= cutoff)
+                })
+                .collect();
+            if kept.len() == content.lines().count() {
+                continue;
+            }
+            let mut text = kept.join("\n");
+            if !kept.is_empty() {
+                text.push('\n');
+            }
+            let tmp = path.with_extension(format!("jsonl.{}.tmp", std::process::id()));
+            fs::write(&tmp, text)?;
+            fs::rename(&tmp, &path)?;
+        }
+        Ok(())
+    }
+}
+
+fn file_stem(session: &str) -> String {
+    let stem: String = session
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if stem.is_empty() {
+        "unknown".to_string()
+    } else {
+        stem
+    }
+}
+
+fn read_state(path: &Path) -> Option<SessionState> {
+    let file = File::open(path).ok()?;
+    let state: SessionState = serde_json::from_reader(io::BufReader::new(file)).ok()?;
+    (state.version == SCHEMA_VERSION).then_some(state)
+}
+
+/// A session document: it has a lock file beside it, or parses as a
+/// [`SessionState`] naming its session (any schema version).
+fn is_session_document(path: &Path) -> bool {
+    if path.with_extension("lock").exists() {
+        return true;
+    }
+    File::open(path)
+        .ok()
+        .and_then(|file| serde_json::from_reader::<_, SessionState>(io::BufReader::new(file)).ok())
+        .is_some_and(|state| !state.session_id.is_empty())
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,156 @@
+use super::*;
+
+fn store() -> (tempfile::TempDir, Store) {
+    let dir = tempfile::tempdir().unwrap();
+    let store = Store::new(dir.path().join("state"), 14);
+    (dir, store)
+}
+
+#[test]
+fn test_load_missing_gives_default() {
+    let (_dir, store) = store();
+    let state = store.load("abc");
+    assert_eq!(state.session_id, "abc");
+    assert!(!state.surface_shown);
+}
+
+#[test]
+fn test_update_persists() {
+    let (_dir, store) = store();
+    store
+        .update("abc", 1000, |s| s.surface_shown = true)
+        .unwrap();
+    let state = store.load("abc");
+    assert!(state.surface_shown);
+    assert_eq!(state.version, SCHEMA_VERSION);
+    assert_eq!(state.updated_at, 1000);
+}
+
+#[test]
+fn test_concurrent_updates_are_not_lost() {
+    let (_dir, store) = store();
+    std::thread::scope(|scope| {
+        for _ in 0..8 {
+            scope.spawn(|| {
+                for _ in 0..25 {
+                    store
+                        .update("abc", 0, |s| s.advisory.tool_calls += 1)
+                        .unwrap();
+                }
+            });
+        }
+    });
+    assert_eq!(store.load("abc").advisory.tool_calls, 200);
+}
+
+#[test]
+fn test_other_schema_version_is_discarded() {
+    let (_dir, store) = store();
+    fs::create_dir_all(store.dir()).unwrap();
+    fs::write(
+        store.path("abc"),
+        r#"{"version": 99, "surface_shown": true}"#,
+    )
+    .unwrap();
+    assert!(!store.load("abc").surface_shown);
+}
+
+#[test]
+fn test_session_id_cannot_escape_dir() {
+    let (_dir, store) = store();
+    let path = store.path("../../etc/passwd");
+    assert_eq!(path.parent(), Some(store.dir()));
+}
+
+#[test]
+fn test_gc_removes_only_stale_sessions() {
+    let (_dir, store) = store();
+    store.update("old", 0, |_| ()).unwrap();
+    store.update("new", 0, |_| ()).unwrap();
+    let old = File::options().write(true).open(store.path("old")).unwrap();
+    old.set_modified(SystemTime::now() - Duration::from_secs(30 * 86_400))
+        .unwrap();
+
+    assert_eq!(store.gc(SystemTime::now()).unwrap(), 1);
+    assert!(!store.path("old").exists());
+    assert!(!store.dir().join("old.lock").exists());
+    assert!(store.path("new").exists());
+}
+
+#[test]
+fn test_gc_keeps_json_that_is_not_a_session() {
+    let (_dir, store) = store();
+    let month_ago = SystemTime::now() - Duration::from_secs(30 * 86_400);
+    fs::create_dir_all(store.dir()).unwrap();
+    for (name, content) in [
+        ("package.json", "{\"name\": \"app\"}"),
+        ("notes.json", "[1, 2]"),
+    ] {
+        let path = store.dir().join(name);
+        fs::write(&path, content).unwrap();
+        File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(month_ago)
+            .unwrap();
+    }
+    let orphan = store.dir().join("orphan.json");
+    fs::write(&orphan, "{\"session_id\": \"orphan\", \"version\": 0}").unwrap();
+    File::options()
+        .write(true)
+        .open(&orphan)
+        .unwrap()
+        .set_modified(month_ago)
+        .unwrap();
+
+    assert_eq!(store.gc(SystemTime::now()).unwrap(), 1);
+    assert!(store.dir().join("package.json").exists());
+    assert!(store.dir().join("notes.json").exists());
+    assert!(!orphan.exists());
+}
+
+#[test]
+fn test_gc_drops_stale_log_entries() {
+    let (_dir, store) = store();
+    let now = SystemTime::now();
+    let recent = i64::try_from(
+        now.duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs(),
+    )
+    .unwrap();
+    for recorded_at in [1000, recent] {
+        store
+            .append_ledger(&LedgerEntry {
+                session_id: "abc".to_string(),
+                recorded_at,
+                cwd: "/vault".to_string(),
+                obligations: Vec::new(),
+            })
+            .unwrap();
+    }
+    fs::write(store.dir().join(AUDIT), "{\"recorded_at\":1000}\n").unwrap();
+
+    store.gc(now).unwrap();
+    let ledger = store.ledger().unwrap();
+    assert_eq!(ledger.len(), 1);
+    assert_eq!(ledger[0].recorded_at, recent);
+    assert_eq!(fs::read_to_string(store.dir().join(AUDIT)).unwrap(), "");
+}
+
+#[test]
+fn test_ledger_take_drains_entries() {
+    let (_dir, store) = store();
+    let entry = LedgerEntry {
+        session_id: "abc".to_string(),
+        recorded_at: 1000,
+        cwd: "/vault".to_string(),
+        obligations: vec!["insight: 1 uncaptured insight(s)".to_string()],
+    };
+    store.append_ledger(&entry).unwrap();
+    store.append_ledger(&entry).unwrap();
+    assert_eq!(store.ledger().unwrap().len(), 2);
+    assert_eq!(store.take_ledger().unwrap(), vec![entry.clone(), entry]);
+    assert!(store.take_ledger().unwrap().is_empty());
+}
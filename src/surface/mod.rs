@@ -306,5 +306,25 @@ pub fn parse_journal_gaps(content: &str) -> Option<String> {
     Some(output)
 }
 
+/// Format obligations left unresolved when earlier sessions ran out of
+/// block budget. Each entry is `(date, obligations)`.
+pub fn format_ledger(entries: &[(NaiveDate, Vec<String>)]) -> Option<String> {
+    if entries
+        .iter()
+        .all(|(_, obligations)| obligations.is_empty())
+    {
+        return None;
+    }
+
+    let mut output = String::from("Unresolved at last exit:\n");
+    for (date, obligations) in entries {
+        for obligation in obligations {
+            let _ = writeln!(output, "  \u{2022} {obligation} ({date})");
+        }
+    }
+
+    Some(output)
+}
+
 #[cfg(test)]
 mod tests;
@@ -318,3 +318,25 @@ fn journal_stops_at_template_embed() {
     assert!(result.contains("Habit one"));
     assert!(!result.contains("should not appear"));
 }
+
+// --- format_ledger ---
+
+#[test]
+fn ledger_lists_obligations_with_dates() {
+    let entries = vec![(
+        date("2026-02-11"),
+        vec![
+            "insight: 1 uncaptured insight(s): Merge Order".to_string(),
+            "reflect: substantial session with no memory writes".to_string(),
+        ],
+    )];
+    let result = format_ledger(&entries).unwrap();
+    assert!(result.starts_with("Unresolved at last exit:"));
+    assert!(result.contains("\u{2022} insight: 1 uncaptured insight(s): Merge Order (2026-02-11)"));
+    assert_eq!(result.lines().count(), 3);
+}
+
+#[test]
+fn ledger_empty_returns_none() {
+    assert!(format_ledger(&[]).is_none());
+}
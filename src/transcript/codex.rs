@@ -0,0 +1,169 @@
+//! Codex CLI rollouts (`~/.codex/sessions/YYYY/MM/DD/rollout-*.jsonl`) —
+//! converted to the Claude-style entries the analyzer walks.
+//!
+//! Current rollouts wrap each line as `{"timestamp", "type", "payload"}`;
+//! only `response_item` and `compacted` lines carry conversation. Older
+//! rollouts write the response items bare. Items:
+//! - `message` with role `user` / `assistant` (`developer` is instructions)
+//! - `function_call` — `shell` / `exec_command` with JSON-string arguments
+//! - `local_shell_call` — `{"action": {"command": [...]}}`
+//! - `custom_tool_call` — `apply_patch` with the patch as `input`
+//!
+//! Shell calls become `shell` tool uses with a string `command`; patches
+//! become one `apply_patch` tool use per added or updated file.
+
+use serde_json::{json, Value};
+
+/// Injected context Codex records as user messages.
+const CONTEXT_PREFIXES: [&str; 3] = [
+    "<environment_context>",
+    "<user_instructions>",
+    "# AGENTS.md instructions",
+];
+
+/// Normalize one rollout line. Lines that are not Codex rollout items pass
+/// through unchanged; rollout bookkeeping (`session_meta`, `turn_context`,
+/// `event_msg`, tool output, reasoning) is dropped.
+pub(super) fn normalize(line: Value) -> Option<Value> {
+    let Some(kind) = line.get("type").and_then(Value::as_str) else {
+        return Some(line);
+    };
+    match kind {
+        "response_item" => item_entry(line.get("payload")?, &line["timestamp"]),
+        // The summary replaces everything before it — mark the boundary the
+        // way Claude Code's continuation message does.
+        "compacted" => Some(json!({
+            "type": "user",
+            "timestamp": line["timestamp"],
+            "message": { "content": [{
+                "type": "text",
+                "text": "Session continued from a previous conversation (Codex compaction)."
+            }]}
+        })),
+        "session_meta" | "turn_context" | "event_msg" => None,
+        "message"
+        | "function_call"
+        | "local_shell_call"
+        | "custom_tool_call"
+        | "function_call_output"
+        | "custom_tool_call_output"
+        | "reasoning" => item_entry(&line, &line["timestamp"]),
+        _ => Some(line),
+    }
+}
+
+fn item_entry(item: &Value, timestamp: &Value) -> Option<Value> {
+    let (entry_type, content) = match item.get("type").and_then(Value::as_str)? {
+        "message" => match item.get("role").and_then(Value::as_str)? {
+            "user" => {
+                let texts = texts(item);
+                if texts.is_empty()
+                    || texts.iter().all(|t| {
+                        CONTEXT_PREFIXES
+                            .iter()
+                            .any(|p| t.trim_start().starts_with(p))
+                    })
+                {
+                    return None;
+                }
+                ("user", text_items(&texts))
+            }
+            "assistant" => ("assistant", text_items(&texts(item))),
+            _ => return None,
+        },
+        "function_call" => {
+            let name = item.get("name").and_then(Value::as_str).unwrap_or_default();
+            let args = item
+                .get("arguments")
+                .and_then(Value::as_str)
+                .and_then(|a| serde_json::from_str::<Value>(a).ok())
+                .unwrap_or_else(|| json!({}));
+            let content = match name {
+                "shell" | "container.exec" | "exec_command" => shell_items(&command_text(
+                    args.get("command").or_else(|| args.get("cmd")),
+                )),
+                "apply_patch" => patch_items(args.get("input").and_then(Value::as_str)),
+                _ => vec![tool_use(name, &args)],
+            };
+            ("assistant", content)
+        }
+        "local_shell_call" => (
+            "assistant",
+            shell_items(&command_text(
+                item.get("action").and_then(|a| a.get("command")),
+            )),
+        ),
+        "custom_tool_call" => {
+            let name = item.get("name").and_then(Value::as_str).unwrap_or_default();
+            let input = item.get("input").and_then(Value::as_str);
+            let content = if name == "apply_patch" {
+                patch_items(input)
+            } else {
+                vec![tool_use(name, &json!({ "input": input }))]
+            };
+            ("assistant", content)
+        }
+        _ => return None,
+    };
+    Some(json!({
+        "type": entry_type,
+        "timestamp": timestamp,
+        "message": { "content": content }
+    }))
+}
+
+/// `input_text` / `output_text` items of a message.
+fn texts(item: &Value) -> Vec<&str> {
+    item.get("content")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|c| c.get("text").and_then(Value::as_str))
+        .collect()
+}
+
+fn text_items(texts: &[&str]) -> Vec<Value> {
+    texts
+        .iter()
+        .map(|text| json!({ "type": "text", "text": text }))
+        .collect()
+}
+
+/// Shell argv (`["bash", "-lc", "..."]`) or a command string, as one string.
+fn command_text(command: Option<&Value>) -> String {
+    match command {
+        Some(Value::String(cmd)) => cmd.clone(),
+        Some(Value::Array(argv)) => argv
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join(" "),
+        _ => String::new(),
+    }
+}
+
+/// A shell call, plus the files of an `apply_patch` heredoc inside it.
+fn shell_items(command: &str) -> Vec<Value> {
+    let mut items = vec![tool_use("shell", &json!({ "command": command }))];
+    if command.contains("*** Begin Patch") {
+        items.extend(patch_items(Some(command)));
+    }
+    items
+}
+
+/// One `apply_patch` tool use per `*** Add File:` / `*** Update File:` path.
+fn patch_items(patch: Option<&str>) -> Vec<Value> {
+    patch
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| {
+            line.strip_prefix("*** Add File:")
+                .or_else(|| line.strip_prefix("*** Update File:"))
+        })
+        .map(|path| tool_use("apply_patch", &json!({ "path": path.trim() })))
+        .collect()
+}
+
+fn tool_use(name: &str, input: &Value) -> Value {
+    json!({ "type": "tool_use", "name": name, "input": input })
+}
@@ -0,0 +1,94 @@
+//! Gemini CLI transcripts — converted to the Claude-style entries the
+//! analyzer walks.
+//!
+//! Two formats, both a single JSON document rather than JSONL:
+//! - chat recordings (`~/.gemini/tmp/<project>/chats/session-*.json`):
+//!   `{"sessionId", "messages": [{"type": "user"|"gemini", "timestamp",
+//!   "content", "toolCalls": [{"name", "args"}]}]}`
+//! - checkpoints (`/chat save`): `[{"role": "user"|"model", "parts":
+//!   [{"text"} | {"functionCall": {"name", "args"}} | {"functionResponse"}]}]`
+
+use serde_json::{json, Value};
+
+/// Entries for a Gemini document, or None when `doc` is neither format.
+pub(super) fn entries(doc: &Value) -> Option<Vec<Value>> {
+    if let Some(messages) = doc.get("messages").and_then(Value::as_array) {
+        return Some(messages.iter().filter_map(chat_entry).collect());
+    }
+    let turns = doc.as_array()?;
+    if !turns
+        .iter()
+        .all(|t| t.get("role").is_some() && t.get("parts").is_some())
+    {
+        return None;
+    }
+    Some(turns.iter().filter_map(checkpoint_entry).collect())
+}
+
+fn chat_entry(message: &Value) -> Option<Value> {
+    let timestamp = message.get("timestamp").cloned().unwrap_or(Value::Null);
+    let mut content = text_items(message.get("content"));
+    match message.get("type").and_then(Value::as_str)? {
+        "user" => Some(json!({
+            "type": "user",
+            "timestamp": timestamp,
+            "message": { "content": content }
+        })),
+        "gemini" => {
+            let calls = message.get("toolCalls").and_then(Value::as_array);
+            content.extend(
+                calls
+                    .into_iter()
+                    .flatten()
+                    .map(|call| tool_use(call.get("name"), call.get("args"))),
+            );
+            Some(json!({
+                "type": "assistant",
+                "timestamp": timestamp,
+                "message": { "content": content }
+            }))
+        }
+        // info, error, warning — UI messages, not turns
+        _ => None,
+    }
+}
+
+fn checkpoint_entry(turn: &Value) -> Option<Value> {
+    let parts = turn.get("parts").and_then(Value::as_array)?;
+    let mut content = Vec::new();
+    for part in parts {
+        if let Some(text) = part.get("text").and_then(Value::as_str) {
+            content.push(json!({ "type": "text", "text": text }));
+        } else if let Some(call) = part.get("functionCall") {
+            content.push(tool_use(call.get("name"), call.get("args")));
+        }
+    }
+    match turn.get("role").and_then(Value::as_str)? {
+        // Tool results come back as user turns holding only functionResponse parts.
+        "user" if content.is_empty() => None,
+        "user" => Some(json!({ "type": "user", "message": { "content": content } })),
+        "model" => Some(json!({ "type": "assistant", "message": { "content": content } })),
+        _ => None,
+    }
+}
+
+/// Chat content is a string or a list of `{"text"}` parts.
+fn text_items(content: Option<&Value>) -> Vec<Value> {
+    match content {
+        Some(Value::String(text)) => vec![json!({ "type": "text", "text": text })],
+        Some(Value::Array(parts)) => parts
+            .iter()
+            .filter_map(|p| p.get("text").and_then(Value::as_str))
+            .map(|text| json!({ "type": "text", "text": text }))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn tool_use(name: Option<&Value>, args: Option<&Value>) -> Value {
+    json!({
+        "type": "tool_use",
+        "name": name.cloned().unwrap_or(Value::Null),
+        "input": args.cloned().unwrap_or_else(|| json!({}))
+    })
+}
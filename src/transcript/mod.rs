@@ -1,7 +1,12 @@
+mod codex;
+mod gemini;
+
 use crate::config::Config;
+use serde::Serialize;
 use serde_json::Value;
 use std::collections::HashSet;
 
+#[derive(Debug, Default, Serialize)]
 pub struct TranscriptAnalysis {
     pub user_messages: usize,
     pub tool_using_turns: usize,
@@ -18,6 +23,37 @@ pub struct TranscriptAnalysis {
     pub skipped_topics: Vec<String>,
     /// Topics explicitly marked as captured via ✓ Insight markers.
     pub captured_topics: Vec<String>,
+    /// Full paths of writes that matched a `memory_paths` fragment.
+    pub memory_writes: Vec<String>,
+    /// Full paths of every write, memory or not, in transcript order.
+    pub file_writes: Vec<String>,
+    /// The latest user prompt, when it contains one of `override_phrases`.
+    /// A later prompt, `SessionReflect` or compaction clears it.
+    pub user_override: Option<UserOverride>,
+    /// User prompts with the number of `file_writes` before each — replay
+    /// rebuilds the session's capture requests from them.
+    #[serde(skip)]
+    pub prompts: Vec<(String, usize)>,
+    /// Stop hook blocks fed back to the agent as user messages.
+    pub stop_feedback: usize,
+    /// Working directory named by the latest entry that has one.
+    #[serde(skip)]
+    pub cwd: Option<String>,
+}
+
+/// Prefix Claude Code gives a Stop hook's block reason when it feeds it
+/// back to the agent as a user message.
+const STOP_FEEDBACK: &str = "Stop hook feedback:";
+
+/// A user's explicit request to end the session without reflecting.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UserOverride {
+    /// The configured phrase that matched.
+    pub phrase: String,
+    /// Slash command arguments, or the text following the phrase.
+    pub reason: String,
+    /// 1-based index of the user message it came from, to log it once.
+    pub message: usize,
 }
 
 impl TranscriptAnalysis {
@@ -28,6 +64,7 @@ impl TranscriptAnalysis {
         self.insights_written.clear();
         self.skipped_topics.clear();
         self.captured_topics.clear();
+        self.user_override = None;
     }
 }
 
@@ -44,39 +81,27 @@ pub fn analyze_transcript(transcript: &str, config: &Config) -> TranscriptAnalys
         session_duration_minutes: 0,
         skipped_topics: Vec::new(),
         captured_topics: Vec::new(),
+        memory_writes: Vec::new(),
+        file_writes: Vec::new(),
+        user_override: None,
+        prompts: Vec::new(),
+        stop_feedback: 0,
+        cwd: None,
     };
 
     let mut first_timestamp: Option<chrono::DateTime<chrono::FixedOffset>> = None;
     let mut last_timestamp: Option<chrono::DateTime<chrono::FixedOffset>> = None;
 
-    // Regex to find ★ Insight blocks and capture the topic.
-    // Anchored to line-start ((?m)^) so prose ABOUT insights doesn't match.
-    // Matches "★ Insight: Topic" and "★ Insight Topic" at start of line.
-    let insight_re = regex::Regex::new(&format!(
-        r"(?m)^\s*`*\s*{}\s*:?\s*(.*)",
-        regex::escape(&config.insight_marker)
-    ))
-    .expect("insight marker regex must compile");
-
-    let skip_re = regex::Regex::new(&format!(
-        r"(?m)^\s*`*\s*{}\s*:?\s*(.*)",
-        regex::escape(&config.insight_skip_marker)
-    ))
-    .expect("insight skip marker regex must compile");
-
+    let insight_re =
+        marker_regex(&config.insight_marker).expect("insight marker regex must compile");
+    let skip_re =
+        marker_regex(&config.insight_skip_marker).expect("insight skip marker regex must compile");
     // Matches "✓ Insight: Topic → filename.md" or "✓ Insight: Topic" (no arrow).
-    let captured_re = regex::Regex::new(&format!(
-        r"(?m)^\s*`*\s*{}\s*:?\s*(.*)",
-        regex::escape(&config.insight_captured_marker)
-    ))
-    .expect("insight captured marker regex must compile");
-
-    for line in transcript.lines() {
-        let entry: Value = match serde_json::from_str(line) {
-            Ok(v) => v,
-            Err(_) => continue,
-        };
+    let captured_re = marker_regex(&config.insight_captured_marker)
+        .expect("insight captured marker regex must compile");
+    let overrides = override_patterns(&config.override_phrases);
 
+    for entry in entries(transcript) {
         if let Some(ts_str) = entry.get("timestamp").and_then(Value::as_str) {
             if let Ok(ts) = chrono::DateTime::parse_from_rfc3339(ts_str) {
                 if first_timestamp.is_none() {
@@ -85,14 +110,12 @@ pub fn analyze_transcript(transcript: &str, config: &Config) -> TranscriptAnalys
                 last_timestamp = Some(ts);
             }
         }
+        if let Some(cwd) = entry.get("cwd").and_then(Value::as_str) {
+            analysis.cwd = Some(cwd.to_string());
+        }
 
         if is_user_entry(&entry) {
-            analysis.user_messages += 1;
-            // Reset insight tracking on compaction boundary — insights from
-            // the previous session were already reviewed/captured there.
-            if is_compaction_boundary(&entry) {
-                analysis.reset_insight_tracking();
-            }
+            record_user_entry(&entry, &overrides, &mut analysis);
             continue;
         }
 
@@ -120,7 +143,7 @@ pub fn analyze_transcript(transcript: &str, config: &Config) -> TranscriptAnalys
                 // SessionReflect resets insight tracking — pre-reflection
                 // insights were reviewed during reflection, so only
                 // post-reflection insights should be checked for capture.
-                if tool_name == "Skill" {
+                if tool_name == "Skill" || tool_name == "activate_skill" {
                     if let Some(skill) = extract_skill_name(item) {
                         if skill == "SessionReflect" {
                             analysis.reset_insight_tracking();
@@ -135,7 +158,7 @@ pub fn analyze_transcript(transcript: &str, config: &Config) -> TranscriptAnalys
 
                 let file_path = if is_write_tool {
                     extract_file_path(item)
-                } else if tool_name == "Bash" {
+                } else if matches!(tool_name, "Bash" | "run_shell_command" | "shell") {
                     extract_bash_command(item).and_then(extract_safe_write_path)
                 } else {
                     None
@@ -146,6 +169,7 @@ pub fn analyze_transcript(transcript: &str, config: &Config) -> TranscriptAnalys
                 };
 
                 check_memory_paths(&mut analysis, &file_path, config);
+                analysis.file_writes.push(file_path);
             }
         }
 
@@ -162,6 +186,35 @@ pub fn analyze_transcript(transcript: &str, config: &Config) -> TranscriptAnalys
     analysis
 }
 
+/// Transcript entries: JSONL lines (Claude Code, Codex rollouts converted
+/// to the same shape), or a Gemini CLI chat recording / checkpoint.
+/// Unparseable lines are skipped.
+fn entries(transcript: &str) -> Vec<Value> {
+    if transcript.trim_start().starts_with(['{', '[']) {
+        if let Some(entries) = serde_json::from_str::<Value>(transcript)
+            .ok()
+            .and_then(|doc| gemini::entries(&doc))
+        {
+            return entries;
+        }
+    }
+    transcript
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .filter_map(codex::normalize)
+        .collect()
+}
+
+/// Regex for a marker block (`★ Insight`, `☆ Insight`, `✓ Insight`) that
+/// captures the topic. Anchored to line-start ((?m)^) so prose ABOUT
+/// insights doesn't match. Matches "★ Insight: Topic" and "★ Insight Topic".
+pub fn marker_regex(marker: &str) -> Result<regex::Regex, regex::Error> {
+    regex::Regex::new(&format!(
+        r"(?m)^\s*`*\s*{}\s*:?\s*(.*)",
+        regex::escape(marker)
+    ))
+}
+
 /// Scan a text block for ★ Insight, ☆ Insight (skip), and ✓ Insight (captured) markers.
 fn scan_text_markers(
     text: &str,
@@ -224,6 +277,99 @@ fn is_compaction_boundary(entry: &Value) -> bool {
     false
 }
 
+/// Count a user entry: override phrase, capture prompt or Stop hook
+/// feedback, and the compaction boundary reset.
+fn record_user_entry(
+    entry: &Value,
+    overrides: &[(&str, regex::Regex)],
+    analysis: &mut TranscriptAnalysis,
+) {
+    analysis.user_messages += 1;
+    // Only the latest prompt overrides: an earlier one was already
+    // answered. Tool results carry no text and leave it standing.
+    let texts = user_texts(entry);
+    if !texts.is_empty() {
+        analysis.user_override = texts
+            .iter()
+            .find_map(|text| find_override(text, overrides, analysis.user_messages));
+    }
+    let boundary = is_compaction_boundary(entry);
+    // The compaction summary quotes prompts that were already counted.
+    for text in texts.into_iter().filter(|_| !boundary) {
+        if text.trim_start().starts_with(STOP_FEEDBACK) {
+            analysis.stop_feedback += 1;
+        } else {
+            let writes = analysis.file_writes.len();
+            analysis.prompts.push((text.to_string(), writes));
+        }
+    }
+    // Reset insight tracking on compaction boundary — insights from
+    // the previous session were already reviewed/captured there.
+    if boundary {
+        analysis.reset_insight_tracking();
+    }
+}
+
+/// Text of a user entry: string content, or the `text` of each content item.
+fn user_texts(entry: &Value) -> Vec<&str> {
+    let content = entry
+        .get("message")
+        .and_then(|m| m.get("content"))
+        .or_else(|| entry.get("content"));
+    match content {
+        Some(Value::String(text)) => vec![text.as_str()],
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|item| item.get("text").and_then(Value::as_str))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Case-insensitive matchers for the non-empty `override_phrases`.
+fn override_patterns(phrases: &[String]) -> Vec<(&str, regex::Regex)> {
+    phrases
+        .iter()
+        .filter(|phrase| !phrase.is_empty())
+        .filter_map(|phrase| {
+            let re = regex::Regex::new(&format!("(?i){}", regex::escape(phrase))).ok()?;
+            Some((phrase.as_str(), re))
+        })
+        .collect()
+}
+
+/// Match the override patterns against user message number `message`. The
+/// reason is the slash command's `<command-args>` when present, else the
+/// rest of the line after the phrase.
+fn find_override(
+    text: &str,
+    patterns: &[(&str, regex::Regex)],
+    message: usize,
+) -> Option<UserOverride> {
+    patterns.iter().find_map(|(phrase, re)| {
+        let found = re.find(text)?;
+        let reason = command_args(text).unwrap_or_else(|| {
+            text[found.end()..]
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .trim_start_matches(|c: char| c.is_whitespace() || ":-\u{2014}".contains(c))
+                .trim()
+        });
+        Some(UserOverride {
+            phrase: (*phrase).to_string(),
+            reason: reason.to_string(),
+            message,
+        })
+    })
+}
+
+fn command_args(text: &str) -> Option<&str> {
+    let start = text.find("<command-args>")? + "<command-args>".len();
+    let end = start + text[start..].find("</command-args>")?;
+    Some(text[start..end].trim())
+}
+
 fn is_user_entry(entry: &Value) -> bool {
     let entry_type = entry.get("type").and_then(Value::as_str).unwrap_or("");
     let role = entry.get("role").and_then(Value::as_str).unwrap_or("");
@@ -308,7 +454,10 @@ fn extract_safe_write_path(command: &str) -> Option<String> {
 }
 
 fn check_memory_paths(analysis: &mut TranscriptAnalysis, file_path: &str, config: &Config) {
-    if file_path.contains(config.insights_path()) {
+    let Some((_, insight)) = config.memory_write(file_path) else {
+        return;
+    };
+    if insight {
         analysis.insights_write_count += 1;
         if let Some(filename) = std::path::Path::new(file_path).file_name() {
             analysis
@@ -316,12 +465,8 @@ fn check_memory_paths(analysis: &mut TranscriptAnalysis, file_path: &str, config
                 .push(filename.to_string_lossy().into_owned());
         }
     }
-
-    for memory_path in &config.memory_paths {
-        if file_path.contains(memory_path.as_str()) {
-            analysis.has_memory_write = true;
-        }
-    }
+    analysis.has_memory_write = true;
+    analysis.memory_writes.push(file_path.to_string());
 }
 
 /// Match insight topic to written filename using word-token overlap.
@@ -360,13 +505,14 @@ fn extract_next_line_topic(remaining: &str) -> Option<String> {
     None
 }
 
-/// Extract the `skill` field from a `Skill` `tool_use` input.
+/// Extract the skill name from a `Skill` (or Gemini `activate_skill`) `tool_use` input.
 fn extract_skill_name(item: &Value) -> Option<&str> {
     let nested = ["input", "tool_input", "arguments"];
     for key in &nested {
         if let Some(skill) = item
             .get(*key)
-            .and_then(|i| i.get("skill"))
+            // Gemini's activate_skill names the skill under `name`
+            .and_then(|i| i.get("skill").or_else(|| i.get("name")))
             .and_then(Value::as_str)
         {
             return Some(skill);
@@ -127,6 +127,32 @@ fn test_insight_with_matching_insight_file() {
     assert!(analysis.has_memory_write);
 }
 
+#[test]
+fn test_write_to_any_vault_memory_dir_counts() {
+    let mut config = cfg();
+    config.memory.insights = "Knowledge/Learned".to_string();
+    config.user.root = "/vaults/personal".to_string();
+    config.user.roots = vec![crate::config::UserRoot {
+        name: "team".to_string(),
+        path: "/vaults/team".to_string(),
+        ..Default::default()
+    }];
+    let transcript = [
+        make_human(),
+        make_assistant_text("\u{2605} Insight \u{2500}\nKey finding\n\u{2500}"),
+        make_assistant_write("/vaults/team/Knowledge/Learned/Key Finding.md"),
+        make_assistant_write("/elsewhere/Knowledge/Learned/Other.md"),
+    ]
+    .join("\n");
+
+    let analysis = analyze_transcript(&transcript, &config);
+    assert_eq!(analysis.insights_write_count, 1);
+    assert_eq!(
+        analysis.memory_writes,
+        vec!["/vaults/team/Knowledge/Learned/Key Finding.md"]
+    );
+}
+
 #[test]
 fn test_no_insights_no_block() {
     let transcript = [
@@ -186,12 +212,16 @@ fn test_imperatives_count_as_memory_not_insights() {
     let analysis = analyze_transcript(&transcript, &cfg());
     assert_eq!(analysis.insights_write_count, 0);
     assert!(analysis.has_memory_write);
+    assert_eq!(analysis.memory_writes.len(), 1);
+    assert!(analysis.memory_writes[0].ends_with("Imperatives/Some Imperative.md"));
 }
 
 #[test]
 fn test_custom_insight_marker() {
-    let mut config = Config::default();
-    config.insight_marker = "CUSTOM_MARKER".to_string();
+    let config = Config {
+        insight_marker: "CUSTOM_MARKER".to_string(),
+        ..Config::default()
+    };
 
     let transcript = [make_human(), make_assistant_text("CUSTOM_MARKER here")].join("\n");
 
@@ -725,3 +755,139 @@ fn test_session_reflect_preserves_session_wide_counters() {
     assert_eq!(analysis.tool_using_turns, 2);
     assert!(analysis.has_memory_write);
 }
+
+// ─── user override ───
+
+fn make_user_text(text: &str) -> String {
+    serde_json::json!({
+        "type": "user",
+        "message": { "role": "user", "content": text }
+    })
+    .to_string()
+}
+
+#[test]
+fn test_override_phrase_with_trailing_reason() {
+    let transcript = [
+        make_user_text("fix the typo"),
+        make_user_text("/noreflect: trivial session, nothing learned"),
+    ]
+    .join("\n");
+    let analysis = analyze_transcript(&transcript, &cfg());
+    let found = analysis.user_override.unwrap();
+    assert_eq!(found.phrase, "/NoReflect");
+    assert_eq!(found.reason, "trivial session, nothing learned");
+}
+
+#[test]
+fn test_override_slash_command_args() {
+    let transcript = make_user_text(
+        "<command-message>NoReflect</command-message>\n\
+         <command-name>/NoReflect</command-name>\n\
+         <command-args>late, heading out</command-args>",
+    );
+    let analysis = analyze_transcript(&transcript, &cfg());
+    assert_eq!(analysis.user_override.unwrap().reason, "late, heading out");
+}
+
+#[test]
+fn test_override_lasts_only_until_the_next_prompt() {
+    let with_override = [
+        make_user_text("/NoReflect done for today"),
+        make_assistant_text("Okay."),
+    ];
+    let analysis = analyze_transcript(&with_override.join("\n"), &cfg());
+    assert_eq!(analysis.user_override.unwrap().message, 1);
+
+    let answered = [&with_override[..], &[make_user_text("one more thing")]].concat();
+    assert!(analyze_transcript(&answered.join("\n"), &cfg())
+        .user_override
+        .is_none());
+
+    let compacted = [
+        make_user_text("/NoReflect"),
+        serde_json::json!({
+            "type": "user",
+            "message": { "content": [{
+                "type": "text",
+                "text": "This session is being continued from a previous conversation."
+            }]}
+        })
+        .to_string(),
+    ];
+    assert!(analyze_transcript(&compacted.join("\n"), &cfg())
+        .user_override
+        .is_none());
+}
+
+#[test]
+fn test_override_ignored_in_assistant_text() {
+    let transcript = [make_human(), make_assistant_text("Use /NoReflect to skip.")].join("\n");
+    assert!(analyze_transcript(&transcript, &cfg())
+        .user_override
+        .is_none());
+}
+
+#[test]
+fn test_gemini_chat_recording() {
+    let transcript = include_str!("../../tests/fixtures/gemini/chat.json");
+    let analysis = analyze_transcript(transcript, &cfg());
+    assert_eq!(analysis.user_messages, 4);
+    assert_eq!(analysis.tool_using_turns, 10);
+    assert_eq!(analysis.insight_count, 1);
+    assert_eq!(analysis.insight_topics, vec!["Config Merge Order"]);
+    assert_eq!(analysis.session_duration_minutes, 16);
+    assert!(!analysis.has_memory_write);
+}
+
+#[test]
+fn test_gemini_checkpoint_counts_write_file() {
+    let transcript = include_str!("../../tests/fixtures/gemini/checkpoint.json");
+    let analysis = analyze_transcript(transcript, &cfg());
+    // the setup context counts; the functionResponse-only user turn is a
+    // tool result, not a message
+    assert_eq!(analysis.user_messages, 2);
+    assert_eq!(analysis.tool_using_turns, 1);
+    assert_eq!(analysis.insights_written, vec!["Config Merge Order.md"]);
+    assert!(analysis.has_memory_write);
+}
+
+#[test]
+fn test_codex_rollout() {
+    let transcript = include_str!("../../tests/fixtures/codex/rollout.jsonl");
+    let analysis = analyze_transcript(transcript, &cfg());
+    // environment context and event_msg echoes are not user messages
+    assert_eq!(analysis.user_messages, 4);
+    assert_eq!(analysis.tool_using_turns, 10);
+    assert_eq!(analysis.insight_topics, vec!["Config Merge Order"]);
+    assert_eq!(analysis.session_duration_minutes, 16);
+    assert!(!analysis.has_memory_write);
+}
+
+#[test]
+fn test_codex_apply_patch_and_shell_safe_write_count_as_writes() {
+    let patch = "*** Begin Patch\n*** Add File: /vault/Memory/Insights/Merge Order.md\n+body\n*** End Patch";
+    let transcript = [
+        serde_json::json!({
+            "type": "response_item",
+            "payload": { "type": "custom_tool_call", "name": "apply_patch", "input": patch }
+        }),
+        serde_json::json!({
+            "type": "response_item",
+            "payload": {
+                "type": "function_call",
+                "name": "shell",
+                "arguments": serde_json::json!({
+                    "command": ["bash", "-lc", "safe-write write /vault/Memory/Imperatives/Rule.md"]
+                }).to_string()
+            }
+        }),
+    ]
+    .map(|line| line.to_string())
+    .join("\n");
+
+    let analysis = analyze_transcript(&transcript, &cfg());
+    assert_eq!(analysis.tool_using_turns, 2);
+    assert_eq!(analysis.insights_written, vec!["Merge Order.md"]);
+    assert_eq!(analysis.memory_writes.len(), 2);
+}
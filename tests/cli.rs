@@ -0,0 +1,1048 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+
+/// Substantial transcript with one uncaptured named insight.
+fn uncaptured_transcript() -> String {
+    let mut lines = Vec::new();
+    for _ in 0..4 {
+        lines.push(serde_json::json!({ "type": "user" }).to_string());
+    }
+    lines.push(
+        serde_json::json!({
+            "type": "assistant",
+            "message": { "content": [
+                { "type": "text", "text": "\u{2605} Insight: Config Merge Order" }
+            ]}
+        })
+        .to_string(),
+    );
+    for _ in 0..10 {
+        lines.push(
+            serde_json::json!({
+                "type": "assistant",
+                "message": { "content": [
+                    { "type": "tool_use", "name": "Read", "input": { "file_path": "/x" } }
+                ]}
+            })
+            .to_string(),
+        );
+    }
+    lines.join("\n")
+}
+
+fn cmd(bin: &str, home: &std::path::Path) -> Command {
+    let mut cmd = Command::cargo_bin(bin).unwrap();
+    cmd.env("HOME", home)
+        .env("XDG_STATE_HOME", home.join("state"))
+        .env("FORGE_MODULE_ROOT", env!("CARGO_MANIFEST_DIR"))
+        .env_remove("FORGE_REFLECT_PROFILE");
+    cmd
+}
+
+#[test]
+fn insight_subcommand_blocks_uncaptured() {
+    let home = tempfile::tempdir().unwrap();
+    let data = home.path().join("Data");
+    fs::create_dir_all(&data).unwrap();
+    let transcript = home.path().join("t.jsonl");
+    fs::write(&transcript, uncaptured_transcript()).unwrap();
+
+    cmd("forge-reflect", home.path())
+        .args(["insight", "--cwd"])
+        .arg(&data)
+        .arg("--transcript")
+        .arg(&transcript)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""decision":"block""#))
+        .stdout(predicate::str::contains("Config Merge Order"));
+}
+
+#[test]
+fn precompact_default_output_is_unchanged() {
+    let home = tempfile::tempdir().unwrap();
+    let data = home.path().join("Data");
+    fs::create_dir_all(&data).unwrap();
+    let transcript = home.path().join("t.jsonl");
+    fs::write(&transcript, uncaptured_transcript()).unwrap();
+
+    let out = cmd("forge-reflect", home.path())
+        .args(["precompact", "--cwd"])
+        .arg(&data)
+        .arg("--transcript")
+        .arg(&transcript)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let out: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    assert_eq!(
+        out["additionalContext"],
+        "STOP — BEFORE COMPACTING, check for uncaptured insights. Any ★ Insight \
+         block in this session that does NOT have a corresponding Memory/Insights/ \
+         file MUST be persisted NOW using the Write tool. Do not compact until all \
+         insights are captured. Apply the reusability filter below: capture reusable \
+         patterns, let one-off traces compact away.Substantial session with no \
+         insights captured. Create a file in Memory/Insights/ or Memory/Imperatives/ \
+         before ending.\n\nUncaptured topics from this session: Config Merge Order."
+    );
+}
+
+#[test]
+fn advisory_insight_is_not_repeated_for_an_unchanged_set() {
+    let home = tempfile::tempdir().unwrap();
+    let data = home.path().join("Data");
+    fs::create_dir_all(&data).unwrap();
+    let transcript = home.path().join("t.jsonl");
+    fs::write(&transcript, uncaptured_transcript()).unwrap();
+
+    let advise = || {
+        let mut cmd = cmd("forge-reflect", home.path());
+        cmd.env("SESSION_ID", "s1")
+            .args(["insight", "--advisory", "--cwd"])
+            .arg(&data)
+            .arg("--transcript")
+            .arg(&transcript);
+        cmd
+    };
+    advise()
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Note: 1"))
+        .stdout(predicate::str::contains("Config Merge Order"));
+    advise()
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+}
+
+#[test]
+fn legacy_alias_reads_stdin_payload() {
+    let home = tempfile::tempdir().unwrap();
+    let data = home.path().join("Data");
+    fs::create_dir_all(&data).unwrap();
+    let transcript = home.path().join("t.jsonl");
+    fs::write(&transcript, uncaptured_transcript()).unwrap();
+    let payload = serde_json::json!({
+        "cwd": data,
+        "transcript_path": transcript,
+    });
+
+    cmd("insight", home.path())
+        .write_stdin(payload.to_string())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""decision":"block""#));
+}
+
+#[test]
+fn analyze_json_reports_uncaptured() {
+    let home = tempfile::tempdir().unwrap();
+    let transcript = home.path().join("t.jsonl");
+    fs::write(&transcript, uncaptured_transcript()).unwrap();
+
+    cmd("forge-reflect", home.path())
+        .args(["analyze", "--format", "json", "--transcript"])
+        .arg(&transcript)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""insight_substantial":true"#))
+        .stdout(predicate::str::contains(
+            r#""topics":["Config Merge Order"]"#,
+        ));
+}
+
+#[test]
+fn explain_traces_decision_on_stderr() {
+    let home = tempfile::tempdir().unwrap();
+    let data = home.path().join("Data");
+    fs::create_dir_all(&data).unwrap();
+    let transcript = home.path().join("t.jsonl");
+    fs::write(&transcript, uncaptured_transcript()).unwrap();
+
+    cmd("forge-reflect", home.path())
+        .args(["insight", "--explain", "--cwd"])
+        .arg(&data)
+        .arg("--transcript")
+        .arg(&transcript)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""decision":"block""#))
+        .stderr(predicate::str::contains("defaults.yaml"))
+        .stderr(predicate::str::contains("tool turns 10 >= 10"))
+        .stderr(predicate::str::contains(
+            "\"Config Merge Order\" \u{2014} UNCAPTURED",
+        ))
+        .stderr(predicate::str::contains("decision:  block"));
+}
+
+#[test]
+fn project_overlay_from_cwd_ancestors_is_merged_last() {
+    let home = tempfile::tempdir().unwrap();
+    let repo = home.path().join("Data/repo");
+    let sub = repo.join("sub");
+    fs::create_dir_all(&sub).unwrap();
+    fs::write(
+        repo.join(".forge-reflect.yaml"),
+        "tool_turn_threshold: 30\n",
+    )
+    .unwrap();
+    fs::write(sub.join(".forge-reflect.yaml"), "tool_turn_threshold: 11\n").unwrap();
+    let transcript = home.path().join("t.jsonl");
+    fs::write(&transcript, uncaptured_transcript()).unwrap();
+
+    cmd("forge-reflect", home.path())
+        .args(["insight", "--explain", "--cwd"])
+        .arg(&sub)
+        .arg("--transcript")
+        .arg(&transcript)
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::contains("repo/.forge-reflect.yaml"))
+        .stderr(predicate::str::contains("repo/sub/.forge-reflect.yaml"))
+        .stderr(predicate::str::contains("tool turns 10 >= 11"));
+}
+
+#[test]
+fn env_override_is_applied_after_yaml_layers() {
+    let home = tempfile::tempdir().unwrap();
+    let data = home.path().join("Data");
+    fs::create_dir_all(&data).unwrap();
+    fs::write(
+        data.join(".forge-reflect.yaml"),
+        "tool_turn_threshold: 30\n",
+    )
+    .unwrap();
+    let transcript = home.path().join("t.jsonl");
+    fs::write(&transcript, uncaptured_transcript()).unwrap();
+
+    cmd("forge-reflect", home.path())
+        .env("FORGE_REFLECT_TOOL_TURN_THRESHOLD", "10")
+        .args(["insight", "--explain", "--cwd"])
+        .arg(&data)
+        .arg("--transcript")
+        .arg(&transcript)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""decision":"block""#))
+        .stderr(predicate::str::contains(
+            "config:    $FORGE_REFLECT_TOOL_TURN_THRESHOLD",
+        ))
+        .stderr(predicate::str::contains("tool turns 10 >= 10"));
+}
+
+#[test]
+fn config_problems_reach_hook_output_once_per_session() {
+    let home = tempfile::tempdir().unwrap();
+    let data = home.path().join("Data");
+    fs::create_dir_all(&data).unwrap();
+    let config = home.path().join("config.yaml");
+    fs::write(
+        &config,
+        "tool_turn_treshold: 5\nblock_budget: lots\nuser_msg_threshold: 5\n",
+    )
+    .unwrap();
+    let transcript = home.path().join("t.jsonl");
+    fs::write(&transcript, uncaptured_transcript()).unwrap();
+    let insight = || {
+        let mut cmd = cmd("forge-reflect", home.path());
+        cmd.env("SESSION_ID", "s1")
+            .args(["insight", "--config"])
+            .arg(&config)
+            .arg("--cwd")
+            .arg(&data)
+            .arg("--transcript")
+            .arg(&transcript);
+        cmd
+    };
+
+    // user_msg_threshold: 5 still applies, so the 4-message session passes.
+    insight()
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("systemMessage"))
+        .stdout(predicate::str::contains(
+            "did you mean `tool_turn_threshold`?",
+        ))
+        .stdout(predicate::str::contains("`block_budget`"));
+    insight()
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+}
+
+#[test]
+fn scope_rule_profile_turns_block_into_warning() {
+    let home = tempfile::tempdir().unwrap();
+    let repo = home.path().join("src/forge");
+    fs::create_dir_all(&repo).unwrap();
+    let transcript = home.path().join("t.jsonl");
+    fs::write(&transcript, uncaptured_transcript()).unwrap();
+    let insight = |config: &str| {
+        let file = home.path().join("config.yaml");
+        fs::write(&file, config).unwrap();
+        let mut cmd = cmd("forge-reflect", home.path());
+        cmd.args(["insight", "--explain", "--config"])
+            .arg(&file)
+            .arg("--cwd")
+            .arg(&repo)
+            .arg("--transcript")
+            .arg(&transcript);
+        cmd
+    };
+
+    insight("{}\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::contains("no scope rule matches"));
+    insight("scope:\n  - include: ~/src/**\n    profile: advisory\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::contains(
+            "profile:   advisory (scope rule 1)",
+        ))
+        .stderr(predicate::str::contains("(profile advisory)"));
+    insight("scope:\n  - include: ~/src/**\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""decision":"block""#));
+}
+
+#[test]
+fn legacy_blocking_switch_off_runs_advisory() {
+    let home = tempfile::tempdir().unwrap();
+    let data = home.path().join("Data");
+    fs::create_dir_all(&data).unwrap();
+    let config = home.path().join("config.yaml");
+    fs::write(&config, "insight_blocking: false\n").unwrap();
+    let transcript = home.path().join("t.jsonl");
+    fs::write(&transcript, uncaptured_transcript()).unwrap();
+
+    cmd("forge-reflect", home.path())
+        .args(["stop", "--explain", "--config"])
+        .arg(&config)
+        .arg("--cwd")
+        .arg(&data)
+        .arg("--transcript")
+        .arg(&transcript)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("decision").not())
+        .stderr(predicate::str::contains("read as `profile: advisory`"))
+        .stderr(predicate::str::contains(
+            "warn \u{2014} 1 uncaptured insight(s)",
+        ));
+}
+
+#[test]
+fn legacy_data_dir_suffix_becomes_the_scope() {
+    let home = tempfile::tempdir().unwrap();
+    let work = home.path().join("Work");
+    let data = home.path().join("Data");
+    fs::create_dir_all(&work).unwrap();
+    fs::create_dir_all(&data).unwrap();
+    let config = home.path().join("config.yaml");
+    fs::write(&config, "data_dir_suffix: Work\n").unwrap();
+    let transcript = home.path().join("t.jsonl");
+    fs::write(&transcript, uncaptured_transcript()).unwrap();
+    let stop = |cwd: &std::path::Path| {
+        let mut cmd = cmd("forge-reflect", home.path());
+        cmd.args(["stop", "--config"])
+            .arg(&config)
+            .arg("--cwd")
+            .arg(cwd)
+            .arg("--transcript")
+            .arg(&transcript);
+        cmd
+    };
+
+    stop(&work)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""decision":"block""#))
+        .stderr(predicate::str::contains(
+            "read as `scope: [{include: ~/Work/**}]`",
+        ));
+    stop(&data)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("decision").not())
+        .stderr(predicate::str::contains("out of scope"));
+}
+
+#[test]
+fn env_profile_overrides_scope_rule_for_one_session() {
+    let home = tempfile::tempdir().unwrap();
+    let repo = home.path().join("src/forge");
+    fs::create_dir_all(&repo).unwrap();
+    let config = home.path().join("config.yaml");
+    fs::write(
+        &config,
+        "scope:\n  - include: ~/src/**\n    profile: advisory\n",
+    )
+    .unwrap();
+    let transcript = home.path().join("t.jsonl");
+    fs::write(&transcript, uncaptured_transcript()).unwrap();
+    let stop = |profile: &str| {
+        let mut cmd = cmd("forge-reflect", home.path());
+        cmd.env("FORGE_REFLECT_PROFILE", profile)
+            .args(["stop", "--explain", "--config"])
+            .arg(&config)
+            .arg("--cwd")
+            .arg(&repo)
+            .arg("--transcript")
+            .arg(&transcript);
+        cmd
+    };
+
+    stop("audit-only")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::contains(
+            "profile:   audit-only ($FORGE_REFLECT_PROFILE)",
+        ))
+        .stderr(predicate::str::contains("decision:  block"));
+    let audit = fs::read_to_string(home.path().join("state/forge-reflect/audit.jsonl")).unwrap();
+    let record: serde_json::Value = serde_json::from_str(audit.trim()).unwrap();
+    assert_eq!(record["command"], "stop");
+    assert_eq!(record["output"]["decision"], "block");
+
+    stop("silent")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::contains(
+            "decision:  allow \u{2014} profile silent",
+        ));
+}
+
+#[test]
+fn posttooluse_surfaces_digest_once_per_session() {
+    let home = tempfile::tempdir().unwrap();
+    let vault = home.path().join("Data");
+    fs::create_dir_all(vault.join("Orchestration")).unwrap();
+    fs::write(
+        vault.join("Orchestration/Backlog.md"),
+        "- [ ] Revisit archived tabs\n",
+    )
+    .unwrap();
+    let payload = serde_json::json!({
+        "session_id": "s1",
+        "cwd": vault,
+        "tool_input": { "skill": "DailyPlan" }
+    })
+    .to_string();
+
+    cmd("forge-reflect", home.path())
+        .arg("posttooluse")
+        .write_stdin(payload.clone())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""hookEventName":"PostToolUse""#))
+        .stdout(predicate::str::contains("VERBATIM"))
+        .stdout(predicate::str::contains("Revisit archived tabs"));
+
+    cmd("forge-reflect", home.path())
+        .arg("posttooluse")
+        .write_stdin(payload)
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+
+    let state: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(home.path().join("state/forge-reflect/s1.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(state["surface_shown"], true);
+    assert_eq!(state["advisory"]["tool_calls"], 1);
+}
+
+/// Hour-long variant of [`uncaptured_transcript`] — trips both Stop gates.
+fn long_uncaptured_transcript() -> String {
+    let mut transcript = uncaptured_transcript();
+    for ts in ["2026-01-01T10:00:00Z", "2026-01-01T11:00:00Z"] {
+        transcript.push('\n');
+        transcript.push_str(&serde_json::json!({ "type": "user", "timestamp": ts }).to_string());
+    }
+    transcript
+}
+
+fn stop_cmd(home: &std::path::Path, transcript: &std::path::Path) -> Command {
+    let mut cmd = cmd("forge-reflect", home);
+    cmd.env("SESSION_ID", "s1")
+        .args(["stop", "--cwd"])
+        .arg(home.join("Data"))
+        .arg("--transcript")
+        .arg(transcript);
+    cmd
+}
+
+#[test]
+fn stop_merges_insight_and_reflect_reasons() {
+    let home = tempfile::tempdir().unwrap();
+    fs::create_dir_all(home.path().join("Data")).unwrap();
+    let path = home.path().join("t.jsonl");
+    fs::write(&path, long_uncaptured_transcript()).unwrap();
+
+    let output = stop_cmd(home.path(), &path)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let block: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(block["decision"], "block");
+    let reason = block["reason"].as_str().unwrap();
+    assert!(reason.starts_with("2 obligations before ending:"));
+    assert!(reason.contains("1. Uncaptured insights detected"));
+    assert!(reason.contains("2. "));
+}
+
+#[test]
+fn stop_budget_records_ledger_and_surface_shows_it() {
+    let home = tempfile::tempdir().unwrap();
+    fs::create_dir_all(home.path().join("Data")).unwrap();
+    let path = home.path().join("t.jsonl");
+    fs::write(&path, long_uncaptured_transcript()).unwrap();
+    let config = home.path().join("config.yaml");
+    fs::write(&config, "block_budget: 1\n").unwrap();
+
+    stop_cmd(home.path(), &path)
+        .arg("--config")
+        .arg(&config)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""decision":"block""#));
+    stop_cmd(home.path(), &path)
+        .arg("--config")
+        .arg(&config)
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::contains("block budget exhausted"));
+
+    // An audited SessionStart digest leaves the ledger for the user.
+    cmd("forge-reflect", home.path())
+        .env("FORGE_REFLECT_PROFILE", "audit-only")
+        .args(["surface", "--format", "json"])
+        .write_stdin(
+            serde_json::json!({
+                "session_id": "s2",
+                "cwd": home.path().join("Data"),
+                "hook_event_name": "SessionStart",
+                "source": "startup"
+            })
+            .to_string(),
+        )
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+    cmd("forge-reflect", home.path())
+        .args(["surface", "--cwd"])
+        .arg(home.path().join("Data"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Unresolved at last exit:"))
+        .stdout(predicate::str::contains(
+            "insight: 1 uncaptured insight(s): Config Merge Order",
+        ));
+}
+
+#[test]
+fn stop_user_override_allows_and_is_audited() {
+    let home = tempfile::tempdir().unwrap();
+    fs::create_dir_all(home.path().join("Data")).unwrap();
+    let mut transcript = long_uncaptured_transcript();
+    transcript.push('\n');
+    transcript.push_str(
+        &serde_json::json!({
+            "type": "user",
+            "message": { "content": "/NoReflect out of time" }
+        })
+        .to_string(),
+    );
+    let path = home.path().join("t.jsonl");
+    fs::write(&path, transcript).unwrap();
+
+    for _ in 0..2 {
+        stop_cmd(home.path(), &path)
+            .assert()
+            .success()
+            .stdout(predicate::str::is_empty());
+    }
+
+    // Logged once, however often the Stop hook runs in that turn.
+    let log = fs::read_to_string(home.path().join("state/forge-reflect/overrides.jsonl")).unwrap();
+    let record: serde_json::Value = serde_json::from_str(log.trim()).unwrap();
+    assert_eq!(record["gate"], "stop");
+    assert_eq!(record["reason"], "out of time");
+    assert_eq!(record["bypassed"].as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn host_flag_selects_response_shape() {
+    let home = tempfile::tempdir().unwrap();
+    let data = home.path().join("Data");
+    fs::create_dir_all(&data).unwrap();
+    let transcript = home.path().join("t.jsonl");
+    fs::write(&transcript, uncaptured_transcript()).unwrap();
+
+    cmd("forge-reflect", home.path())
+        .args(["insight", "--host", "gemini", "--cwd"])
+        .arg(&data)
+        .arg("--transcript")
+        .arg(&transcript)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""decision":"deny""#));
+    cmd("forge-reflect", home.path())
+        .args(["insight", "--host", "opencode", "--cwd"])
+        .arg(&data)
+        .arg("--transcript")
+        .arg(&transcript)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""action":"toast""#));
+}
+
+#[test]
+fn gemini_after_agent_payload_is_detected_and_denied() {
+    let home = tempfile::tempdir().unwrap();
+    let data = home.path().join("Data");
+    fs::create_dir_all(&data).unwrap();
+    let chats = home
+        .path()
+        .join(".gemini/tmp/69e3624ac739013d8e87d0d1746ad587f320f6c9904b0ee9cb16487324a58dbe/chats");
+    fs::create_dir_all(&chats).unwrap();
+    let transcript = chats.join("session-2026-10-19T09-00-dc070dec.json");
+    fs::copy("tests/fixtures/gemini/chat.json", &transcript).unwrap();
+
+    let mut payload: serde_json::Value =
+        serde_json::from_str(include_str!("fixtures/gemini/after_agent.json")).unwrap();
+    payload["cwd"] = serde_json::json!(data);
+    payload["transcript_path"] = serde_json::json!(transcript);
+
+    cmd("forge-reflect", home.path())
+        .arg("stop")
+        .write_stdin(payload.to_string())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""decision":"deny""#))
+        .stdout(predicate::str::contains("Config Merge Order"));
+}
+
+#[test]
+fn hooks_prints_gemini_settings_and_codex_notify() {
+    let home = tempfile::tempdir().unwrap();
+    let output = cmd("forge-reflect", home.path())
+        .args(["hooks", "gemini"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let settings: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let command = settings["hooks"]["AfterAgent"][0]["hooks"][0]["command"]
+        .as_str()
+        .unwrap();
+    assert!(command.ends_with("forge-reflect stop --host gemini"));
+
+    cmd("forge-reflect", home.path())
+        .args(["hooks", "codex"])
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with("\"notify\"]\n"));
+    cmd("forge-reflect", home.path())
+        .args(["hooks", "claude"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("hooks/hooks.json"));
+}
+
+#[test]
+fn codex_notify_records_pending_reflection() {
+    let home = tempfile::tempdir().unwrap();
+    let data = home.path().join("Data");
+    fs::create_dir_all(&data).unwrap();
+    let mut payload: serde_json::Value =
+        serde_json::from_str(include_str!("fixtures/codex/notify.json")).unwrap();
+    let thread = payload["thread-id"].as_str().unwrap().to_string();
+    let today = chrono::Local::now().date_naive();
+    let day = home
+        .path()
+        .join(".codex/sessions")
+        .join(today.format("%Y/%m/%d").to_string());
+    fs::create_dir_all(&day).unwrap();
+    let rollout = day.join(format!("rollout-{today}T09-00-00-{thread}.jsonl"));
+    fs::copy("tests/fixtures/codex/rollout.jsonl", &rollout).unwrap();
+    payload["cwd"] = serde_json::json!(data);
+
+    cmd("forge-reflect", home.path())
+        .env_remove("CODEX_HOME")
+        .arg("notify")
+        .arg(payload.to_string())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""type":"notify""#))
+        .stdout(predicate::str::contains("Config Merge Order"));
+
+    let state = fs::read_to_string(
+        home.path()
+            .join(format!("state/forge-reflect/{thread}.json")),
+    )
+    .unwrap();
+    let state: serde_json::Value = serde_json::from_str(&state).unwrap();
+    assert_eq!(state["rollout"], rollout.display().to_string());
+    let pending = &state["pending_reflection"];
+    assert_eq!(pending["obligations"].as_array().unwrap().len(), 1);
+    assert!(pending["reason"]
+        .as_str()
+        .unwrap()
+        .contains("Config Merge Order"));
+}
+
+#[test]
+fn codex_notify_finds_and_caches_an_older_rollout() {
+    let home = tempfile::tempdir().unwrap();
+    let mut payload: serde_json::Value =
+        serde_json::from_str(include_str!("fixtures/codex/notify.json")).unwrap();
+    let thread = payload["thread-id"].as_str().unwrap().to_string();
+    // Started a week ago: outside the dated search for today and yesterday.
+    let day = home.path().join(".codex/sessions/2026/01/05");
+    fs::create_dir_all(&day).unwrap();
+    let rollout = day.join(format!("rollout-2026-01-05T09-00-00-{thread}.jsonl"));
+    fs::copy("tests/fixtures/codex/rollout.jsonl", &rollout).unwrap();
+    // Out of scope, so the turn is skipped after the search.
+    payload["cwd"] = serde_json::json!(home.path().join("elsewhere"));
+
+    cmd("forge-reflect", home.path())
+        .env_remove("CODEX_HOME")
+        .arg("notify")
+        .arg(payload.to_string())
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(format!(
+            "no rollout for '{thread}' dated"
+        )));
+
+    let state = fs::read_to_string(
+        home.path()
+            .join(format!("state/forge-reflect/{thread}.json")),
+    )
+    .unwrap();
+    let state: serde_json::Value = serde_json::from_str(&state).unwrap();
+    assert_eq!(state["rollout"], rollout.display().to_string());
+}
+
+#[test]
+fn session_start_surfaces_once_and_again_after_compaction() {
+    let home = tempfile::tempdir().unwrap();
+    let vault = home.path().join("Data");
+    fs::create_dir_all(vault.join("Orchestration")).unwrap();
+    fs::write(
+        vault.join("Orchestration/Backlog.md"),
+        "- [ ] Revisit archived tabs\n",
+    )
+    .unwrap();
+    let payload = |source: &str| {
+        serde_json::json!({
+            "session_id": "s1",
+            "cwd": vault,
+            "hook_event_name": "SessionStart",
+            "source": source
+        })
+        .to_string()
+    };
+    let surface = || {
+        let mut cmd = cmd("forge-reflect", home.path());
+        cmd.args(["surface", "--format", "json"]);
+        cmd
+    };
+
+    surface()
+        .write_stdin(payload("startup"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            r#""hookEventName":"SessionStart""#,
+        ))
+        .stdout(predicate::str::contains("Revisit archived tabs"));
+    surface()
+        .write_stdin(payload("resume"))
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+    // startup claimed the session's showing — the first journals skill stays quiet
+    cmd("forge-reflect", home.path())
+        .arg("posttooluse")
+        .write_stdin(
+            serde_json::json!({
+                "session_id": "s1",
+                "cwd": vault,
+                "tool_input": { "skill": "DailyPlan" }
+            })
+            .to_string(),
+        )
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+
+    // compact is not in the default surface.on
+    surface()
+        .write_stdin(payload("compact"))
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+    let config = home.path().join("config.yaml");
+    fs::write(&config, "surface:\n  on: [compact]\n").unwrap();
+    surface()
+        .arg("--config")
+        .arg(&config)
+        .write_stdin(payload("compact"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Revisit archived tabs"));
+}
+
+#[test]
+fn capture_request_is_injected_and_enforced_at_stop() {
+    let home = tempfile::tempdir().unwrap();
+    let data = home.path().join("Data");
+    fs::create_dir_all(&data).unwrap();
+    let transcript = home.path().join("t.jsonl");
+    fs::write(&transcript, "").unwrap();
+
+    cmd("forge-reflect", home.path())
+        .arg("userpromptsubmit")
+        .write_stdin(
+            serde_json::json!({
+                "session_id": "s1",
+                "cwd": data,
+                "transcript_path": transcript,
+                "hook_event_name": "UserPromptSubmit",
+                "prompt": "Remember that the staging DB resets nightly."
+            })
+            .to_string(),
+        )
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            r#""hookEventName":"UserPromptSubmit""#,
+        ))
+        .stdout(predicate::str::contains("Orchestration/Memory/Insights"));
+
+    stop_cmd(home.path(), &transcript)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("capture request(s)"))
+        .stdout(predicate::str::contains("the staging DB resets nightly"));
+
+    let write = serde_json::json!({
+        "type": "assistant",
+        "message": { "content": [{
+            "type": "tool_use",
+            "name": "Write",
+            "input": { "file_path": data.join("Orchestration/Memory/Insights/Staging Resets.md") }
+        }]}
+    });
+    fs::write(&transcript, write.to_string()).unwrap();
+    stop_cmd(home.path(), &transcript)
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+}
+
+#[test]
+fn capture_request_is_ignored_when_silent_or_out_of_scope() {
+    let home = tempfile::tempdir().unwrap();
+    let data = home.path().join("Data");
+    let elsewhere = home.path().join("Elsewhere");
+    fs::create_dir_all(&data).unwrap();
+    fs::create_dir_all(&elsewhere).unwrap();
+
+    for (cwd, profile) in [(&data, "silent"), (&elsewhere, "strict")] {
+        cmd("forge-reflect", home.path())
+            .env("FORGE_REFLECT_PROFILE", profile)
+            .arg("userpromptsubmit")
+            .write_stdin(
+                serde_json::json!({
+                    "session_id": "s1",
+                    "cwd": cwd,
+                    "transcript_path": home.path().join("t.jsonl"),
+                    "hook_event_name": "UserPromptSubmit",
+                    "prompt": "Remember that the staging DB resets nightly."
+                })
+                .to_string(),
+            )
+            .assert()
+            .success()
+            .stdout(predicate::str::is_empty());
+        assert!(!home.path().join("state/forge-reflect/s1.json").exists());
+    }
+}
+
+#[test]
+fn capture_write_outside_the_vault_does_not_count() {
+    let home = tempfile::tempdir().unwrap();
+    let data = home.path().join("Data");
+    fs::create_dir_all(&data).unwrap();
+    let transcript = home.path().join("t.jsonl");
+    fs::write(&transcript, "").unwrap();
+
+    cmd("forge-reflect", home.path())
+        .arg("userpromptsubmit")
+        .write_stdin(
+            serde_json::json!({
+                "session_id": "s1",
+                "cwd": data,
+                "transcript_path": transcript,
+                "hook_event_name": "UserPromptSubmit",
+                "prompt": "Remember that the staging DB resets nightly."
+            })
+            .to_string(),
+        )
+        .assert()
+        .success();
+
+    let write = serde_json::json!({
+        "type": "assistant",
+        "message": { "content": [{
+            "type": "tool_use",
+            "name": "Write",
+            "input": { "file_path": "/tmp/Orchestration/Memory/Insights/Staging Resets.md" }
+        }]}
+    });
+    fs::write(&transcript, write.to_string()).unwrap();
+    stop_cmd(home.path(), &transcript)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("the staging DB resets nightly"));
+
+    // Under `advisory` the capture gate only warns, like the others.
+    stop_cmd(home.path(), &transcript)
+        .env("FORGE_REFLECT_PROFILE", "advisory")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+}
+
+#[test]
+fn tagged_capture_request_is_routed_to_its_vault() {
+    let home = tempfile::tempdir().unwrap();
+    let data = home.path().join("Data");
+    fs::create_dir_all(&data).unwrap();
+    fs::write(
+        data.join(".forge-reflect.yaml"),
+        "user:\n  roots:\n    - { name: team, path: team, tags: [team] }\n",
+    )
+    .unwrap();
+    let transcript = home.path().join("t.jsonl");
+    fs::write(&transcript, "").unwrap();
+    let team_dir = home.path().join("team/Orchestration/Memory/Insights");
+
+    cmd("forge-reflect", home.path())
+        .arg("userpromptsubmit")
+        .write_stdin(
+            serde_json::json!({
+                "session_id": "s1",
+                "cwd": data,
+                "transcript_path": transcript,
+                "hook_event_name": "UserPromptSubmit",
+                "prompt": "Remember that #team deploys freeze on Fridays."
+            })
+            .to_string(),
+        )
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(team_dir.display().to_string()));
+
+    let write = serde_json::json!({
+        "type": "assistant",
+        "message": { "content": [{
+            "type": "tool_use",
+            "name": "Write",
+            "input": { "file_path": team_dir.join("Deploy Freeze.md") }
+        }]}
+    });
+    fs::write(&transcript, write.to_string()).unwrap();
+    stop_cmd(home.path(), &transcript)
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+}
+
+#[test]
+fn config_get_reads_the_merged_config() {
+    let home = tempfile::tempdir().unwrap();
+    let repo = home.path().join("repo");
+    fs::create_dir_all(&repo).unwrap();
+    fs::write(
+        repo.join(".forge-reflect.yaml"),
+        "promote:\n  steering: Rules\n",
+    )
+    .unwrap();
+
+    let get = |args: &[&str]| {
+        let mut cmd = cmd("forge-reflect", home.path());
+        cmd.args(["config", "get"])
+            .args(args)
+            .arg("--cwd")
+            .arg(&repo);
+        cmd
+    };
+    get(&["promote.steering"])
+        .assert()
+        .success()
+        .stdout("Rules\n");
+    get(&["surface.max_items"])
+        .env("FORGE_REFLECT_SURFACE__MAX_ITEMS", "2")
+        .assert()
+        .success()
+        .stdout("2\n");
+    get(&["skills.codex.PendingReflection"])
+        .assert()
+        .success()
+        .stdout("\n");
+    get(&["stop_gates", "--format", "json"])
+        .assert()
+        .success()
+        .stdout("[\"insight\",\"reflect\",\"capture\"]\n");
+    get(&["promote.steerin"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("did you mean `promote.steering`?"));
+}
+
+#[test]
+fn config_get_into_a_closed_pipe_succeeds() {
+    let home = tempfile::tempdir().unwrap();
+    let (reader, writer) = std::io::pipe().unwrap();
+    drop(reader);
+    let output = std::process::Command::new(assert_cmd::cargo::cargo_bin("forge-reflect"))
+        .env("HOME", home.path())
+        .env("XDG_STATE_HOME", home.path().join("state"))
+        .env("FORGE_MODULE_ROOT", env!("CARGO_MANIFEST_DIR"))
+        .args(["config", "get", "stop_gates"])
+        .stdout(writer)
+        .stderr(std::process::Stdio::piped())
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    assert!(output.stderr.is_empty(), "{output:?}");
+}
+
+#[test]
+fn config_schema_prints_the_shipped_schema() {
+    let home = tempfile::tempdir().unwrap();
+    cmd("forge-reflect", home.path())
+        .args(["config", "schema"])
+        .assert()
+        .success()
+        .stdout(include_str!("../config.schema.json"));
+}